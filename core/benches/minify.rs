@@ -0,0 +1,46 @@
+// Times minification of pathological single-line inputs of increasing size. Run with
+// `cargo bench -p const-css-minify-core`. If minification is linear in the length of the input,
+// the time per MiB should stay roughly constant as the input grows.
+
+use const_css_minify_core::{Minifier, MinifyOptions};
+use std::hint::black_box;
+use std::time::Instant;
+
+const MIB: usize = 1024 * 1024;
+
+// a single line with a backreference in every selector and every declaration
+fn selectors(len: usize) -> String {
+    let rule = ".a :hover , .b :focus-visible > .c :not( .d ) { color :red ; margin : 0 auto } ";
+    rule.repeat(len / rule.len())
+}
+
+// a single line holding one giant declaration with a backreference ahead of it
+fn declaration(len: usize) -> String {
+    let mut css = String::from("a{grid-template-areas :");
+    css.push_str(&" \"a b\"".repeat(len / 6));
+    css.push('}');
+    css
+}
+
+fn bench(name: &str, generate: fn(usize) -> String) {
+    for mib in [1, 2, 4, 8] {
+        let css = generate(mib * MIB);
+        let start = Instant::now();
+        let mut minifier = Minifier::new(MinifyOptions::default());
+        minifier.minify_string(&css);
+        black_box(minifier.get_output());
+        let elapsed = start.elapsed();
+        println!(
+            "{:<12} {:>2} MiB {:>10.2?} {:>10.2?}/MiB",
+            name,
+            mib,
+            elapsed,
+            elapsed / mib as u32
+        );
+    }
+}
+
+fn main() {
+    bench("selectors", selectors);
+    bench("declaration", declaration);
+}
@@ -0,0 +1,2488 @@
+//! The css minifier behind [`const-css-minify`](https://docs.rs/const-css-minify).
+//!
+//! Most users will want the `minify!()` macro from `const-css-minify`, which minifies css at
+//! compile time. This crate exposes the same minifier for use at runtime or in build scripts, for
+//! the rare cases where the macro isn't flexible enough:
+//!
+//! ```rust
+//! use const_css_minify_core::minify_str;
+//!
+//! assert_eq!(minify_str("div { color: #ffffff; }"), "div{color:#fff}");
+//! ```
+//!
+//! Values of particular properties can be copied as they are, e.g. for properties with an unusual
+//! syntax:
+//!
+//! ```rust
+//! use const_css_minify_core::minify_with_predicate;
+//!
+//! let css = minify_with_predicate(
+//!     r#"div { grid-template-areas: "a b"  "c d" ; color: #ffffff; }"#,
+//!     |property| property == "grid-template-areas",
+//! );
+//! assert_eq!(css, r#"div{grid-template-areas:"a b" "c d";color:#fff}"#);
+//! ```
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, Read};
+use std::str::FromStr;
+
+/// Minify css with the default options, exactly as the `minify!()` macro does
+pub fn minify_str(css: &str) -> String {
+    let mut minifier = Minifier::new(MinifyOptions::default());
+    minifier.minify_string(css);
+    minifier.get_output()
+}
+
+/// Minify css, copying the value of any declaration for which `preserve` returns true for the
+/// property name as it is
+///
+/// Preserved values still have their whitespace collapsed and comments removed, but are otherwise
+/// untouched, e.g. colors are not minified.
+pub fn minify_with_predicate<F: Fn(&str) -> bool>(css: &str, preserve: F) -> String {
+    let css = css.to_string();
+    let mut minifier = Minifier::new(MinifyOptions::default());
+    minifier.preserve = Some(&preserve);
+    minifier.minify_string(&css);
+    minifier.get_output()
+}
+
+/// Minify css with the default options, also counting the transforms applied
+pub fn minify_with_stats(css: &str) -> (String, TransformStats) {
+    let mut minifier = Minifier::new(MinifyOptions::default());
+    minifier.minify_string(css);
+    let stats = minifier.stats().clone();
+    (minifier.get_output(), stats)
+}
+
+/// True if `b` may be part of a css identifier
+#[doc(hidden)]
+pub fn is_ident_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'-' || b == b'_' || b >= 0x80
+}
+
+/// Opt-in transforms, enabled by passing the field name as an option to the macro
+#[derive(Clone, Default)]
+pub struct MinifyOptions {
+    pub collapse_shorthands: bool,
+    pub collapse_zeros: bool,
+    pub dedupe_declarations: bool,
+    pub inline_imports: bool,
+    pub lossless_alpha: bool,
+    pub no_color: bool,
+    pub quiet: bool,
+    pub shortest_colors: bool,
+    pub strip_empty_declarations: bool,
+    pub strip_empty_rules: bool,
+    /// Number of fractional digits to which decimals in values are rounded, if any
+    pub round_decimals: Option<usize>,
+    /// Largest error, out of 255, permitted in a color channel decoded from a color function, if
+    /// any. Functions whose channels can't be represented within this error are left as they are.
+    pub max_color_error: Option<f32>,
+    /// Prefixes of property names whose values are copied as they are
+    pub verbatim: Vec<String>,
+}
+
+impl MinifyOptions {
+    /// Enable the option with the given name
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no such option.
+    pub fn set(&mut self, option: &str) {
+        match option {
+            "collapse_shorthands" => self.collapse_shorthands = true,
+            "collapse_zeros" => self.collapse_zeros = true,
+            "dedupe_declarations" => self.dedupe_declarations = true,
+            "inline_imports" => self.inline_imports = true,
+            "lossless_alpha" => self.lossless_alpha = true,
+            "max_color_error" => self.max_color_error = Some(0.0),
+            "no_color" => self.no_color = true,
+            "quiet" => self.quiet = true,
+            "round_decimals" => self.round_decimals = Some(DEFAULT_ROUND_DECIMALS),
+            "shortest_colors" => self.shortest_colors = true,
+            "strip_empty_declarations" => self.strip_empty_declarations = true,
+            "strip_empty_rules" => self.strip_empty_rules = true,
+            _ => panic!("const_css_minify does not recognise option: {}", option),
+        }
+    }
+
+    /// Set the option with the given name to a value
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no such option taking a value.
+    pub fn set_value(&mut self, option: &str, value: &str) {
+        match option {
+            "round_decimals" => {
+                self.round_decimals = Some(usize::from_str(value).unwrap_or_else(|_| {
+                    panic!(
+                        "const_css_minify round_decimals must be a number: {}",
+                        value
+                    )
+                }))
+            }
+            "max_color_error" => {
+                self.max_color_error = Some(f32::from_str(value).unwrap_or_else(|_| {
+                    panic!(
+                        "const_css_minify max_color_error must be a number: {}",
+                        value
+                    )
+                }))
+            }
+            "verbatim" => self.verbatim.push(value.to_string()),
+            _ => panic!("const_css_minify does not recognise option: {}", option),
+        }
+    }
+}
+
+/// Counts of some of the transforms applied while minifying, and the bytes they saved
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TransformStats {
+    /// Colors rewritten in a different form, e.g. `#ffffff` as `#fff`
+    pub colors: usize,
+    pub color_bytes_saved: usize,
+    /// Comments removed
+    pub comments: usize,
+    pub comment_bytes_saved: usize,
+    /// Redundant semicolons removed, each of which saves a byte
+    pub semicolons: usize,
+}
+
+/// Sizes of the css before and after minifying, and the bytes saved
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MinifyStats {
+    pub input_len: usize,
+    pub output_len: usize,
+    pub bytes_saved: usize,
+    /// Bytes saved as a percentage of the input length
+    pub percent_saved: f32,
+}
+
+impl fmt::Display for MinifyStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "minified {} bytes to {}, saving {} ({:.1}%)",
+            self.input_len, self.output_len, self.bytes_saved, self.percent_saved
+        )
+    }
+}
+
+/// A syntax error encountered while minifying
+#[derive(Debug)]
+pub struct ParseError {
+    msg: String,
+    offset: usize,
+}
+
+impl ParseError {
+    pub fn from_msg(msg: &str, offset: usize) -> Self {
+        Self {
+            msg: msg.to_string(),
+            offset,
+        }
+    }
+
+    /// The byte offset in the input at which the error was found
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.msg)
+    }
+}
+
+// we do not attempt to decode all valid rgb func expressions, but we do attempt simple expressions
+// that consist of purely literal numeric expressions.
+const RGB_FUNC_DECODABLE: [u8; 15] = [
+    b'0', b'1', b'2', b'3', b'4', b'5', b'6', b'7', b'8', b'9', b' ', b',', b'%', b'.', b'/',
+];
+
+// fractional digits to which decimals are rounded by the `round_decimals` option, if not given
+const DEFAULT_ROUND_DECIMALS: usize = 4;
+
+// functions whose arguments have an unusual syntax, which we don't attempt to minify
+const VERBATIM_FUNCTIONS: [&[u8]; 1] = [b"-webkit-gradient("];
+
+// functions in at-rule preludes which hold a declaration or media feature, like a bare paren
+const CONDITION_FUNCTIONS: [&[u8]; 2] = [b"supports", b"media"];
+
+// math functions, in which whitespace around `*` is optional, ignoring any vendor prefix
+const MATH_FUNCTIONS: [&[u8]; 21] = [
+    b"abs", b"acos", b"asin", b"atan", b"atan2", b"calc", b"clamp", b"cos", b"exp", b"hypot",
+    b"log", b"max", b"min", b"mod", b"pow", b"rem", b"round", b"sign", b"sin", b"sqrt", b"tan",
+];
+
+// functional pseudo classes taking an an+b argument
+const NTH_PSEUDO_CLASSES: [&[u8]; 4] = [
+    b":nth-child(",
+    b":nth-last-child(",
+    b":nth-of-type(",
+    b":nth-last-of-type(",
+];
+
+// tolerance for floating point error when checking a channel maps exactly to a byte
+const BYTE_EPSILON: f32 = 0.001;
+
+// font-weight keywords and the shorter numbers to which they are equal
+const FONT_WEIGHTS: [(&[u8], &[u8]); 2] = [(b"bold", b"700"), (b"normal", b"400")];
+
+// shorthand properties taking a list of 1-4 lengths, one for each side (or corner), for which a
+// list of all zeros is equivalent to a single zero
+const BOX_SHORTHANDS: [&[u8]; 13] = [
+    b"margin",
+    b"margin-block",
+    b"margin-inline",
+    b"padding",
+    b"padding-block",
+    b"padding-inline",
+    b"inset",
+    b"inset-block",
+    b"inset-inline",
+    b"border-width",
+    b"border-radius",
+    b"scroll-margin",
+    b"scroll-padding",
+];
+
+// units for which a zero length is always equivalent to a unitless zero. A zero percentage isn't,
+// e.g. `flex-basis:0%` differs from `flex-basis:0` where the size of the container is indefinite
+const LENGTH_UNITS: [&[u8]; 31] = [
+    b"px", b"em", b"rem", b"ex", b"rex", b"ch", b"rch", b"cap", b"rcap", b"ic", b"ric", b"lh",
+    b"rlh", b"vw", b"vh", b"vi", b"vb", b"vmin", b"vmax", b"svh", b"lvh", b"dvh", b"svw", b"lvw",
+    b"dvw", b"cm", b"mm", b"q", b"in", b"pt", b"pc",
+];
+
+// properties other than the `*-color` longhands whose value may be a single color
+const COLOR_PROPERTIES: [&[u8]; 19] = [
+    b"color",
+    b"background",
+    b"border",
+    b"border-top",
+    b"border-right",
+    b"border-bottom",
+    b"border-left",
+    b"border-block",
+    b"border-block-start",
+    b"border-block-end",
+    b"border-inline",
+    b"border-inline-start",
+    b"border-inline-end",
+    b"outline",
+    b"column-rule",
+    b"text-decoration",
+    b"text-emphasis",
+    b"fill",
+    b"stroke",
+];
+
+// the transparent keyword, which is longer than the equivalent hex color
+const TRANSPARENT: &[u8] = b"transparent";
+
+// named colors which are longer than the equivalent hex color
+const NAMED_COLORS: [(&[u8], &[u8]); 104] = [
+    (b"aliceblue", b"#f0f8ff"),
+    (b"antiquewhite", b"#faebd7"),
+    (b"aquamarine", b"#7fffd4"),
+    (b"black", b"#000"),
+    (b"blanchedalmond", b"#ffebcd"),
+    (b"blueviolet", b"#8a2be2"),
+    (b"burlywood", b"#deb887"),
+    (b"cadetblue", b"#5f9ea0"),
+    (b"chartreuse", b"#7fff00"),
+    (b"chocolate", b"#d2691e"),
+    (b"cornflowerblue", b"#6495ed"),
+    (b"cornsilk", b"#fff8dc"),
+    (b"darkblue", b"#00008b"),
+    (b"darkcyan", b"#008b8b"),
+    (b"darkgoldenrod", b"#b8860b"),
+    (b"darkgray", b"#a9a9a9"),
+    (b"darkgreen", b"#006400"),
+    (b"darkgrey", b"#a9a9a9"),
+    (b"darkkhaki", b"#bdb76b"),
+    (b"darkmagenta", b"#8b008b"),
+    (b"darkolivegreen", b"#556b2f"),
+    (b"darkorange", b"#ff8c00"),
+    (b"darkorchid", b"#9932cc"),
+    (b"darksalmon", b"#e9967a"),
+    (b"darkseagreen", b"#8fbc8f"),
+    (b"darkslateblue", b"#483d8b"),
+    (b"darkslategray", b"#2f4f4f"),
+    (b"darkslategrey", b"#2f4f4f"),
+    (b"darkturquoise", b"#00ced1"),
+    (b"darkviolet", b"#9400d3"),
+    (b"deeppink", b"#ff1493"),
+    (b"deepskyblue", b"#00bfff"),
+    (b"dodgerblue", b"#1e90ff"),
+    (b"firebrick", b"#b22222"),
+    (b"floralwhite", b"#fffaf0"),
+    (b"forestgreen", b"#228b22"),
+    (b"fuchsia", b"#f0f"),
+    (b"gainsboro", b"#dcdcdc"),
+    (b"ghostwhite", b"#f8f8ff"),
+    (b"goldenrod", b"#daa520"),
+    (b"greenyellow", b"#adff2f"),
+    (b"honeydew", b"#f0fff0"),
+    (b"indianred", b"#cd5c5c"),
+    (b"lavender", b"#e6e6fa"),
+    (b"lavenderblush", b"#fff0f5"),
+    (b"lawngreen", b"#7cfc00"),
+    (b"lemonchiffon", b"#fffacd"),
+    (b"lightblue", b"#add8e6"),
+    (b"lightcoral", b"#f08080"),
+    (b"lightcyan", b"#e0ffff"),
+    (b"lightgoldenrodyellow", b"#fafad2"),
+    (b"lightgray", b"#d3d3d3"),
+    (b"lightgreen", b"#90ee90"),
+    (b"lightgrey", b"#d3d3d3"),
+    (b"lightpink", b"#ffb6c1"),
+    (b"lightsalmon", b"#ffa07a"),
+    (b"lightseagreen", b"#20b2aa"),
+    (b"lightskyblue", b"#87cefa"),
+    (b"lightslategray", b"#789"),
+    (b"lightslategrey", b"#789"),
+    (b"lightsteelblue", b"#b0c4de"),
+    (b"lightyellow", b"#ffffe0"),
+    (b"limegreen", b"#32cd32"),
+    (b"magenta", b"#f0f"),
+    (b"mediumaquamarine", b"#66cdaa"),
+    (b"mediumblue", b"#0000cd"),
+    (b"mediumorchid", b"#ba55d3"),
+    (b"mediumpurple", b"#9370db"),
+    (b"mediumseagreen", b"#3cb371"),
+    (b"mediumslateblue", b"#7b68ee"),
+    (b"mediumspringgreen", b"#00fa9a"),
+    (b"mediumturquoise", b"#48d1cc"),
+    (b"mediumvioletred", b"#c71585"),
+    (b"midnightblue", b"#191970"),
+    (b"mintcream", b"#f5fffa"),
+    (b"mistyrose", b"#ffe4e1"),
+    (b"moccasin", b"#ffe4b5"),
+    (b"navajowhite", b"#ffdead"),
+    (b"olivedrab", b"#6b8e23"),
+    (b"orangered", b"#ff4500"),
+    (b"palegoldenrod", b"#eee8aa"),
+    (b"palegreen", b"#98fb98"),
+    (b"paleturquoise", b"#afeeee"),
+    (b"palevioletred", b"#db7093"),
+    (b"papayawhip", b"#ffefd5"),
+    (b"peachpuff", b"#ffdab9"),
+    (b"powderblue", b"#b0e0e6"),
+    (b"rebeccapurple", b"#639"),
+    (b"rosybrown", b"#bc8f8f"),
+    (b"royalblue", b"#4169e1"),
+    (b"saddlebrown", b"#8b4513"),
+    (b"sandybrown", b"#f4a460"),
+    (b"seagreen", b"#2e8b57"),
+    (b"seashell", b"#fff5ee"),
+    (b"slateblue", b"#6a5acd"),
+    (b"slategray", b"#708090"),
+    (b"slategrey", b"#708090"),
+    (b"springgreen", b"#00ff7f"),
+    (b"steelblue", b"#4682b4"),
+    (b"turquoise", b"#40e0d0"),
+    (b"white", b"#fff"),
+    (b"whitesmoke", b"#f5f5f5"),
+    (b"yellow", b"#ff0"),
+    (b"yellowgreen", b"#9acd32"),
+];
+
+// named colors which are shorter than the equivalent hex color, keyed by the shortest hex color
+const SHORT_NAMED_COLORS: [(&[u8], &[u8]); 31] = [
+    (b"#f0ffff", b"azure"),
+    (b"#f5f5dc", b"beige"),
+    (b"#ffe4c4", b"bisque"),
+    (b"#a52a2a", b"brown"),
+    (b"#ff7f50", b"coral"),
+    (b"#ffd700", b"gold"),
+    (b"#808080", b"gray"),
+    (b"#008000", b"green"),
+    (b"#4b0082", b"indigo"),
+    (b"#fffff0", b"ivory"),
+    (b"#f0e68c", b"khaki"),
+    (b"#faf0e6", b"linen"),
+    (b"#800000", b"maroon"),
+    (b"#000080", b"navy"),
+    (b"#808000", b"olive"),
+    (b"#ffa500", b"orange"),
+    (b"#da70d6", b"orchid"),
+    (b"#cd853f", b"peru"),
+    (b"#ffc0cb", b"pink"),
+    (b"#dda0dd", b"plum"),
+    (b"#800080", b"purple"),
+    (b"#f00", b"red"),
+    (b"#fa8072", b"salmon"),
+    (b"#a0522d", b"sienna"),
+    (b"#c0c0c0", b"silver"),
+    (b"#fffafa", b"snow"),
+    (b"#d2b48c", b"tan"),
+    (b"#008080", b"teal"),
+    (b"#ff6347", b"tomato"),
+    (b"#ee82ee", b"violet"),
+    (b"#f5deb3", b"wheat"),
+];
+
+// record the decision taken for the byte at read in pass1, when built with the `trace` feature
+macro_rules! trace {
+    ($minifier:expr, $input:expr, $read:expr, $decision:expr) => {
+        #[cfg(feature = "trace")]
+        $minifier.trace($input, $read, $decision);
+    };
+}
+
+/*
+ * css is relatively simple but there are a few gotchas. Nested classes basically means any
+ * property can be a selector, so we can't generically distinguish between the two without
+ * a lookup to known legal names, and also the fact that pseudo classes and elements are
+ * denoted with ':' which is also the value assignment operator means we need to scan ahead to
+ * decide if a particular ':' on the input is part of a selector and requires leading
+ * whitespace to be preserved, or if it's the assignment operator and doesn't require leading
+ * whitespace. To avoid re-implementing comment and quote handling while scanning forward, we
+ * instead mark the index as a backreference and remove it later if we can. This also has the
+ * conseqence that we also cannot generically identify if we are currently parsing a property
+ * or a value without a lookup to known legal names, which as far as I know shouldn't cause
+ * problems for handling correct css but eliminates some avenues for error tolerance. But
+ * intelligent handling of incorrect css is beyond this scope of this crate so this is
+ * acceptable.
+ *
+ * Removing a backreference shifts the remainder of the output, but a backreference is forgotten
+ * at every ':', '{', ';' and '}', so the remainder is never more than the tail of the current
+ * declaration, which is removed at most once. The total cost of removals is therefore linear in
+ * the length of the input, however many selectors or declarations it contains.
+ */
+pub struct Minifier<'a> {
+    options: MinifyOptions,
+    // declarations for whose property this returns true are copied as they are
+    preserve: Option<&'a dyn Fn(&str) -> bool>,
+    input: Option<Cow<'a, [u8]>>,
+    output0: Vec<u8>,
+    output1: Vec<u8>,
+    // start and end indexes of quotes, and of comments which are preserved
+    quotes0: HashMap<usize, usize>,
+    // index in the input of each byte of output0, for locating errors
+    offsets0: Vec<usize>,
+    errors: Vec<ParseError>,
+    stats: TransformStats,
+    #[cfg(feature = "trace")]
+    trace_log: Vec<String>,
+}
+
+impl<'a> Minifier<'a> {
+    /// The minified css, once `minify_string()` has been called
+    pub fn get_output(self) -> String {
+        String::from_utf8(self.output1).unwrap()
+    }
+
+    /// Create a minifier applying the given options
+    pub fn new(options: MinifyOptions) -> Self {
+        Self {
+            options,
+            preserve: None,
+            input: None,
+            output0: Vec::<u8>::with_capacity(0),
+            output1: Vec::<u8>::with_capacity(0),
+            quotes0: HashMap::<usize, usize>::new(),
+            offsets0: Vec::<usize>::with_capacity(0),
+            errors: Vec::<ParseError>::new(),
+            stats: TransformStats::default(),
+            #[cfg(feature = "trace")]
+            trace_log: Vec::<String>::new(),
+        }
+    }
+
+    /// Minify the input, collecting any syntax errors encountered. The output, errors and stats of
+    /// any previous input are discarded.
+    pub fn minify_string(&mut self, input: &'a str) {
+        self.reset(Cow::Borrowed(input.as_bytes()));
+        self.pass0();
+        self.pass1();
+    }
+
+    /// Minify the css read from a reader, e.g. a file in a build script, collecting any syntax
+    /// errors encountered. The whole of the css is buffered, as quotes and comments are tracked
+    /// across the whole input.
+    ///
+    /// # Errors
+    ///
+    /// Fails if reading fails, or if the css isn't valid utf-8.
+    pub fn minify_reader<R: Read>(&mut self, mut reader: R) -> io::Result<()> {
+        let mut input = String::new();
+        reader.read_to_string(&mut input)?;
+        self.reset(Cow::Owned(input.into_bytes()));
+        self.pass0();
+        self.pass1();
+        Ok(())
+    }
+
+    // clear everything left over from minifying any previous input, so that a minifier may be
+    // reused
+    fn reset(&mut self, input: Cow<'a, [u8]>) {
+        self.input = Some(input);
+        self.output0.clear();
+        self.output1.clear();
+        self.quotes0.clear();
+        self.offsets0.clear();
+        self.errors.clear();
+        self.stats = TransformStats::default();
+        #[cfg(feature = "trace")]
+        self.trace_log.clear();
+    }
+
+    /// The decision taken for each byte scanned in the second pass, one per line, in the form
+    /// `index 'byte' decision`
+    #[cfg(feature = "trace")]
+    pub fn trace_log(&self) -> &[String] {
+        &self.trace_log
+    }
+
+    #[cfg(feature = "trace")]
+    fn trace(&mut self, input: &[u8], read: usize, decision: &str) {
+        let line = format!("{} {:?} {}", read, char::from(input[read]), decision);
+        eprintln!("TRACE const-css-minify: {}", line);
+        self.trace_log.push(line);
+    }
+
+    fn add_error_msg(&mut self, msg: &str, offset: usize) {
+        self.errors.push(ParseError::from_msg(msg, offset));
+    }
+
+    /// Counts of the transforms applied, once `minify_string()` has been called
+    pub fn stats(&self) -> &TransformStats {
+        &self.stats
+    }
+
+    /// Sizes of the input and output, once `minify_string()` has been called
+    pub fn size_stats(&self) -> MinifyStats {
+        let input_len = self.input.as_ref().map_or(0, |input| input.len());
+        let output_len = self.output1.len();
+        let bytes_saved = input_len.saturating_sub(output_len);
+        let percent_saved = if input_len == 0 {
+            0_f32
+        } else {
+            bytes_saved as f32 * 100_f32 / input_len as f32
+        };
+        MinifyStats {
+            input_len,
+            output_len,
+            bytes_saved,
+            percent_saved,
+        }
+    }
+
+    // count a color rewritten from one form to another
+    fn count_color(&mut self, from: &[u8], to: &[u8]) {
+        if from != to {
+            self.stats.colors += 1;
+            self.stats.color_bytes_saved += from.len().saturating_sub(to.len());
+        }
+    }
+
+    // drop a fully opaque alpha channel from a minified hex color, and substitute a shorter named
+    // color, if shortest_colors is set. Colors in custom properties are left as hex, as they may
+    // be read by scripts.
+    fn shorten_color(&self, mut hex_color: Vec<u8>, in_value: bool) -> Vec<u8> {
+        if !self.options.shortest_colors || !in_value {
+            return hex_color;
+        }
+        let alpha = 1 + (hex_color.len() - 1) / 4 * 3;
+        if [5, 9].contains(&hex_color.len())
+            && hex_color[alpha..]
+                .iter()
+                .all(|b| b.eq_ignore_ascii_case(&b'f'))
+        {
+            hex_color.truncate(alpha);
+            hex_color = try_minify_hex_color(&hex_color).unwrap();
+        }
+        match SHORT_NAMED_COLORS
+            .iter()
+            .find(|(hex, _)| hex.eq_ignore_ascii_case(&hex_color))
+        {
+            Some((_, name)) => name.to_vec(),
+            None => hex_color,
+        }
+    }
+
+    /// The line and column, both counted from 1, of a byte offset in the input, e.g. that of a
+    /// syntax error. Columns are counted in chars rather than bytes.
+    pub fn line_col(&self, offset: usize) -> (usize, usize) {
+        let input = self.input.as_deref().unwrap_or_default();
+        let before = &input[..offset.min(input.len())];
+        // a byte order mark isn't a column
+        let bom = if input.starts_with("\u{feff}".as_bytes()) {
+            3
+        } else {
+            0
+        };
+        let line_start = before
+            .iter()
+            .rposition(|b| *b == b'\n')
+            .map_or(bom.min(before.len()), |i| i + 1);
+        let line = before.iter().filter(|b| **b == b'\n').count() + 1;
+        // skip utf-8 continuation bytes
+        let col = before[line_start..]
+            .iter()
+            .filter(|b| **b & 0xc0 != 0x80)
+            .count()
+            + 1;
+        (line, col)
+    }
+
+    /// Take the syntax errors encountered, e.g. to report them other than by `emit_error_msgs()`
+    pub fn take_errors(&mut self) -> Vec<ParseError> {
+        std::mem::take(&mut self.errors)
+    }
+
+    /// Print any syntax errors encountered to stderr, unless the `quiet` option is set
+    pub fn emit_error_msgs(&self) {
+        if self.options.quiet {
+            return;
+        }
+        for error in &self.errors {
+            let (line, col) = self.line_col(error.offset());
+            eprintln!(
+                "WARN! const-css-minify parse error: {} at line {}, col {}",
+                error, line, col
+            );
+        }
+    }
+
+    //collapse all whitespace sequences into single ' ', remove comments,
+    //mark quotes in output stream
+    fn pass0(&mut self) {
+        // take the input so we are free to mutate self while reading, and put it back at the end
+        let input = self.input.take().unwrap();
+        let len = input.len();
+        let mut output = Vec::<u8>::with_capacity(len);
+        // a byte order mark is meaningless in utf-8, and would precede any `@charset`, which must
+        // be the very first thing in a stylesheet
+        let mut read = if input.starts_with("\u{feff}".as_bytes()) {
+            3
+        } else {
+            0
+        };
+        // end of the last comment preserved in output
+        let mut preserved_end = None;
+        loop {
+            match read {
+                i if i == len => break,
+                i if i > len => unreachable!(), // to catch errors of reasoning in indexing
+                _ => (),
+            }
+            let offset = read;
+            match input[read] {
+                // trim excess whitespace, convert to space
+                w if w.is_ascii_whitespace() => {
+                    // if the last element was a comment that was entirely ignored, and if the
+                    // comment was preceeded by whitespace, we might end up with two consecutive
+                    // whitespaces, which violates the promise of this method. Thus we explicitly
+                    // check and remove it if present.
+                    if let Some(last) = output.pop() {
+                        if last != b' ' {
+                            output.push(last);
+                        }
+                    }
+                    read += 1;
+                    while read < len && input[read].is_ascii_whitespace() {
+                        read += 1;
+                    }
+                    // don't add whitespace to head or tail, or following a preserved comment
+                    if !output.is_empty() && read < len && preserved_end != Some(output.len()) {
+                        output.push(b' ');
+                    }
+                }
+                // css comments
+                b'/' if len > read + 1 && input[read + 1] == b'*' => {
+                    let mut found_end = false;
+                    // move read index to first char after '*' in the matched pattern, or possibly
+                    // past the end of input if '/*' are the last two chars.
+                    read += 2;
+                    // below we are comparing against a '*' at read - 1, and we explicitly want to
+                    // avoid opening and closing a comment on '/*/' - a correct comment consists of
+                    // '/**/ at a minimum. Therefore we must increment read once more, but we only
+                    // want to do this if we aren't already beyond the end of input
+                    if read < len {
+                        read += 1;
+                    }
+                    while read < len {
+                        let s = &input[read - 1..=read];
+                        read += 1;
+                        if s == [b'*', b'/'] {
+                            found_end = true;
+                            break;
+                        }
+                    }
+                    if !found_end {
+                        self.add_error_msg("reached end of input while inside comment", offset);
+                    }
+                    // preserve important comments, such as licenses
+                    if found_end && input[offset + 2] == b'!' {
+                        self.quotes0
+                            .insert(output.len(), output.len() + read - offset - 1);
+                        output.extend_from_slice(&input[offset..read]);
+                        preserved_end = Some(output.len());
+                        self.offsets0.resize(output.len(), offset);
+                        continue;
+                    }
+                    self.stats.comments += 1;
+                    self.stats.comment_bytes_saved += read - offset;
+                    // a comment separates tokens, so replace it with a space if the tokens either
+                    // side of it would otherwise run together, e.g. `1px/**/2px`
+                    if output.last().is_some_and(|b| is_ident_byte(*b))
+                        && input.get(read).is_some_and(|b| is_ident_byte(*b))
+                    {
+                        output.push(b' ');
+                        self.stats.comment_bytes_saved -= 1;
+                    }
+                }
+                // quotes
+                q @ (b'"' | b'\'') => {
+                    let start = output.len();
+                    output.push(input[read]);
+                    read += 1;
+                    let mut found_end = false;
+                    while read < len {
+                        let b = input[read];
+                        output.push(b);
+                        read += 1;
+                        if b == q {
+                            found_end = true;
+                            break;
+                        }
+                        // an escaped quote doesn't end the string
+                        if b == b'\\' && read < len {
+                            output.push(input[read]);
+                            read += 1;
+                        }
+                    }
+                    if !found_end {
+                        self.add_error_msg(
+                            "reached end of input while inside quote string",
+                            offset,
+                        );
+                    }
+                    let end = output.len() - 1;
+                    self.quotes0.insert(start, end);
+                }
+                _ => {
+                    output.push(input[read]);
+                    read += 1;
+                }
+            }
+            // whatever was output (or removed) above came from the input at offset
+            self.offsets0.resize(output.len(), offset);
+        }
+        self.output0 = output;
+        self.input = Some(input);
+    }
+
+    fn pass1(&mut self) {
+        // take ownership of the intermediate output so we are free to mutate self while reading
+        let input = std::mem::take(&mut self.output0);
+        let len = input.len();
+        let mut output = Vec::<u8>::with_capacity(len);
+        let mut read = 0;
+        let mut peek;
+        let mut backreference = None;
+        // start of the current declaration (or selector) and index of its ':', if any
+        let mut declaration_start = 0;
+        let mut assignment = None;
+        let mut assignment_offset = 0;
+        // in the prelude of an at-rule, and for each paren open in it, whether the paren is bare
+        // (or belongs to a condition function) rather than belonging to any other function
+        let mut at_rule = false;
+        let mut prelude_parens = Vec::new();
+        // depth of parens open in the current declaration
+        let mut parens = 0_usize;
+        // for each paren open in the current declaration, whether it belongs to a math function
+        // (or is a bare paren inside one)
+        let mut math_parens = Vec::new();
+        // for each block open, the start of its selector (or at-rule prelude) and index of its '{'
+        let mut blocks = Vec::new();
+        // start and end of the previous declaration in the current block, if it was the last thing
+        let mut previous_declaration = None;
+        loop {
+            match read {
+                i if i == len => break,
+                i if i > len => unreachable!(), // to catch errors of reasoning in indexing
+                _ => (),
+            }
+            match input[read] {
+                // copy quotes verbatim
+                b'\'' | b'"' => {
+                    trace!(self, &input, read, "quote");
+                    let end = self.quotes0.get(&read).unwrap();
+                    while read <= *end {
+                        output.push(input[read]);
+                        read += 1
+                    }
+                }
+                // copy preserved comments verbatim
+                b'/' if self
+                    .quotes0
+                    .get(&read)
+                    .is_some_and(|end| input.get(read..=*end).is_some()) =>
+                {
+                    trace!(self, &input, read, "preserved comment");
+                    let end = *self.quotes0.get(&read).unwrap();
+                    output.extend_from_slice(&input[read..=end]);
+                    read = end + 1;
+                }
+                // enter declaration block
+                b'{' => {
+                    trace!(self, &input, read, "enter block");
+                    backreference = None;
+                    assignment = None;
+                    at_rule = false;
+                    prelude_parens.clear();
+                    parens = 0;
+                    math_parens.clear();
+                    if let Some(last) = output.pop() {
+                        if last != b' ' {
+                            output.push(last);
+                        }
+                    }
+                    output.push(input[read]);
+                    blocks.push((declaration_start, output.len() - 1));
+                    previous_declaration = None;
+                    declaration_start = output.len();
+                    read += 1;
+                    // drop trailing space
+                    if read < len && input[read] == b' ' {
+                        read += 1;
+                    }
+                }
+                // exit declaration block
+                b'}' => {
+                    trace!(self, &input, read, "exit block");
+                    at_rule = false;
+                    prelude_parens.clear();
+                    parens = 0;
+                    math_parens.clear();
+                    if let Some(br) = backreference {
+                        output.remove(br);
+                        assignment = assignment.map(|a| if a > br { a - 1 } else { a });
+                    }
+                    backreference = None;
+                    if let Some(last) = output.pop() {
+                        if last != b' ' {
+                            output.push(last);
+                        }
+                    }
+                    if let Some(a) = assignment {
+                        self.finish_declaration(
+                            &mut output,
+                            declaration_start,
+                            a,
+                            assignment_offset,
+                        );
+                        if self.is_duplicate(&output, declaration_start, previous_declaration) {
+                            output.truncate(declaration_start);
+                        }
+                    }
+                    assignment = None;
+                    previous_declaration = None;
+                    // drop final semicolon in declaration block
+                    if let Some(last) = output.pop() {
+                        if last != b';' {
+                            output.push(last);
+                        } else {
+                            self.stats.semicolons += 1;
+                        }
+                    }
+                    match blocks.pop() {
+                        // drop the whole rule if its block is empty
+                        Some((start, brace))
+                            if self.options.strip_empty_rules
+                                && output[brace + 1..].iter().all(|b| *b == b';')
+                                && is_removable_when_empty(&output[start..brace]) =>
+                        {
+                            output.truncate(start);
+                        }
+                        _ => output.push(input[read]),
+                    }
+                    declaration_start = output.len();
+                    read += 1;
+                    // drop trailing space
+                    if read < len && input[read] == b' ' {
+                        read += 1;
+                    }
+                }
+                // value assignement OR pseudo class/element
+                b':' => {
+                    trace!(self, &input, read, "colon");
+                    backreference = None;
+                    // pseudo element
+                    if len > read + 1 && input[read + 1] == b':' {
+                        output.push(b':');
+                        output.push(b':');
+                        read += 2;
+                    } else if at_rule {
+                        // an at-rule prelude has no declarations, but a bare paren in it may
+                        // hold a media feature or a declaration, e.g. `@supports (a:b)`, whereas
+                        // a function may hold a selector, e.g. `@supports selector(a :hover)`
+                        if prelude_parens.last() == Some(&true) && output.last() == Some(&b' ') {
+                            output.pop();
+                        }
+                        output.push(input[read]);
+                        read += 1;
+                        // drop trailing space
+                        if read < len && input[read] == b' ' {
+                            read += 1;
+                        }
+                    } else {
+                        if let Some(last) = output.pop() {
+                            // mark backreference for possible future removal
+                            if last == b' ' {
+                                backreference = Some(output.len());
+                            }
+                            output.push(last);
+                        }
+                        let first_assignment = assignment.is_none();
+                        if first_assignment {
+                            assignment = Some(output.len());
+                            assignment_offset = self.offsets0[read];
+                        }
+                        output.push(input[read]);
+                        read += 1;
+                        // drop trailing space
+                        if read < len && input[read] == b' ' {
+                            read += 1;
+                        }
+                        if first_assignment && self.preserves(&output[declaration_start..]) {
+                            // copy value as it is, up to the end of the declaration
+                            while read < len && ![b';', b'{', b'}'].contains(&input[read]) {
+                                if let Some(end) = self.quotes0.get(&read) {
+                                    output.extend_from_slice(&input[read..=*end]);
+                                    read = end + 1;
+                                } else {
+                                    output.push(input[read]);
+                                    read += 1;
+                                }
+                            }
+                        }
+                    }
+                }
+                // open paren
+                b'(' => {
+                    trace!(self, &input, read, "open paren");
+                    parens += 1;
+                    let name_start = output
+                        .iter()
+                        .rposition(|b| !is_ident_byte(*b))
+                        .map_or(0, |i| i + 1);
+                    let name = &output[name_start..];
+                    let name = [&b"-webkit-"[..], b"-moz-"]
+                        .iter()
+                        .find_map(|prefix| name.strip_prefix(*prefix))
+                        .unwrap_or(name);
+                    math_parens.push(if name.is_empty() {
+                        math_parens.last() == Some(&true)
+                    } else {
+                        MATH_FUNCTIONS.iter().any(|f| f.eq_ignore_ascii_case(name))
+                    });
+                    if at_rule {
+                        // some conditions hold a declaration or media feature in a function,
+                        // e.g. `@import url(a.css) supports(display:grid)`
+                        let condition = CONDITION_FUNCTIONS.iter().any(|f| {
+                            output.len() >= f.len()
+                                && output[output.len() - f.len()..].eq_ignore_ascii_case(f)
+                        });
+                        prelude_parens
+                            .push(condition || output.last().is_none_or(|b| !is_ident_byte(*b)));
+                    }
+                    output.push(input[read]);
+                    read += 1;
+                    // drop trailing space
+                    if read < len && input[read] == b' ' {
+                        read += 1;
+                    }
+                    // whitespace in the an+b microsyntax is insignificant, except that the
+                    // selector list of an `of` clause must be separated from it
+                    if NTH_PSEUDO_CLASSES.iter().any(|p| {
+                        output.len() >= p.len()
+                            && output[output.len() - p.len()..].eq_ignore_ascii_case(p)
+                    }) {
+                        while read < len && input[read] != b')' {
+                            if input[read] == b' ' {
+                                if input[read + 1..].starts_with(b"of ") {
+                                    output.push(b' ');
+                                    read += 1;
+                                    break;
+                                }
+                            } else {
+                                output.push(input[read]);
+                            }
+                            read += 1;
+                        }
+                    }
+                }
+                // close paren
+                b')' => {
+                    trace!(self, &input, read, "close paren");
+                    parens = parens.saturating_sub(1);
+                    math_parens.pop();
+                    prelude_parens.pop();
+                    // drop preceding space
+                    if let Some(last) = output.pop() {
+                        if last != b' ' {
+                            output.push(last);
+                        }
+                    }
+                    output.push(input[read]);
+                    read += 1;
+                }
+                // slash separator in a value or at-rule prelude, e.g. `font:12px/1.5 serif`
+                b'/' if assignment.is_some() || at_rule => {
+                    trace!(self, &input, read, "slash");
+                    // drop preceding space
+                    if let Some(last) = output.pop() {
+                        if last != b' ' {
+                            output.push(last);
+                        }
+                    }
+                    output.push(input[read]);
+                    read += 1;
+                    // drop trailing space
+                    if read < len && input[read] == b' ' {
+                        read += 1;
+                    }
+                }
+                // multiplication in a math function, e.g. `calc(2 * 1em)`, which unlike addition and
+                // subtraction doesn't require surrounding whitespace
+                b'*' if math_parens.last() == Some(&true) => {
+                    trace!(self, &input, read, "multiplication");
+                    // drop preceding space
+                    if let Some(last) = output.pop() {
+                        if last != b' ' {
+                            output.push(last);
+                        }
+                    }
+                    output.push(input[read]);
+                    read += 1;
+                    // drop trailing space
+                    if read < len && input[read] == b' ' {
+                        read += 1;
+                    }
+                }
+                // range comparison in a media feature, e.g. `(200px < width <= 400px)`
+                b'<' | b'>' | b'=' if at_rule && prelude_parens.last() == Some(&true) => {
+                    trace!(self, &input, read, "range comparison");
+                    // drop preceding space
+                    if let Some(last) = output.pop() {
+                        if last != b' ' {
+                            output.push(last);
+                        }
+                    }
+                    output.push(input[read]);
+                    read += 1;
+                    // drop trailing space
+                    if read < len && input[read] == b' ' {
+                        read += 1;
+                    }
+                }
+                // selector combinator, e.g. `a > b`. A `+` may also be the sign of a number or an
+                // operator in a function such as calc(), where it must be surrounded by spaces
+                b'>' | b'~' | b'+'
+                    if !at_rule
+                        && !output[declaration_start..].starts_with(b"--")
+                        && (input[read] != b'+'
+                            || parens == 0
+                                && input
+                                    .get(read + 1)
+                                    .is_none_or(|b| !b.is_ascii_digit() && *b != b'.')) =>
+                {
+                    trace!(self, &input, read, "combinator");
+                    // drop preceding space
+                    if let Some(last) = output.pop() {
+                        if last != b' ' {
+                            output.push(last);
+                        }
+                    }
+                    output.push(input[read]);
+                    read += 1;
+                    // drop trailing space
+                    if read < len && input[read] == b' ' {
+                        read += 1;
+                    }
+                }
+                // comma separator
+                b',' => {
+                    trace!(self, &input, read, "comma");
+                    // drop spaces preceeding commas
+                    if let Some(last) = output.pop() {
+                        if last != b' ' {
+                            output.push(last);
+                        }
+                    }
+                    output.push(input[read]);
+                    read += 1;
+                    // drop trailing space
+                    if read < len && input[read] == b' ' {
+                        read += 1;
+                    }
+                }
+                // semicolon separator
+                b';' => {
+                    trace!(self, &input, read, "semicolon");
+                    at_rule = false;
+                    prelude_parens.clear();
+                    parens = 0;
+                    math_parens.clear();
+                    if let Some(br) = backreference {
+                        output.remove(br);
+                        assignment = assignment.map(|a| if a > br { a - 1 } else { a });
+                    }
+                    backreference = None;
+                    // drop leading space
+                    if let Some(last) = output.pop() {
+                        if last != b' ' {
+                            output.push(last);
+                        }
+                    }
+                    let removed = assignment.is_some_and(|a| {
+                        self.finish_declaration(
+                            &mut output,
+                            declaration_start,
+                            a,
+                            assignment_offset,
+                        )
+                    });
+                    let duplicate = !removed
+                        && assignment.is_some()
+                        && self.is_duplicate(&output, declaration_start, previous_declaration);
+                    if duplicate {
+                        output.truncate(declaration_start);
+                    }
+                    // an empty declaration is dropped, keeping the declaration before it
+                    let empty = assignment.is_none() && matches!(output.last(), Some(b'{' | b';'));
+                    // a removed declaration takes its semicolon with it, as does a duplicate
+                    if !removed && !duplicate && !empty {
+                        previous_declaration =
+                            assignment.map(|_| (declaration_start, output.len()));
+                        output.push(input[read]);
+                    }
+                    assignment = None;
+                    declaration_start = output.len();
+                    read += 1;
+                    // drop trailing space
+                    if read < len && input[read] == b' ' {
+                        read += 1;
+                    }
+                }
+                // attribute selector, or anything else in brackets such as grid line names
+                b'[' => {
+                    trace!(self, &input, read, "attribute selector");
+                    match minify_attribute_selector(&input, read, &self.quotes0) {
+                        Some((mut selector, end)) => {
+                            output.append(&mut selector);
+                            read = end + 1;
+                        }
+                        None => {
+                            output.push(input[read]);
+                            read += 1;
+                        }
+                    }
+                }
+                // at-rule
+                b'@' => {
+                    trace!(self, &input, read, "at-rule");
+                    at_rule = true;
+                    output.push(input[read]);
+                    read += 1;
+                }
+                // legacy function with a syntax of its own, copied verbatim
+                b'-' if assignment.is_some()
+                    && output.last().is_none_or(|b| !is_ident_byte(*b))
+                    && VERBATIM_FUNCTIONS.iter().any(|f| {
+                        input.len() > read + f.len()
+                            && input[read..read + f.len()].eq_ignore_ascii_case(f)
+                    }) =>
+                {
+                    trace!(self, &input, read, "verbatim function");
+                    let mut depth = 0;
+                    while read < len {
+                        match (input[read], self.quotes0.get(&read).copied()) {
+                            // a quote or preserved comment, which may contain parens
+                            (b'\'' | b'"' | b'/', Some(end)) => {
+                                output.extend_from_slice(&input[read..=end]);
+                                read = end + 1;
+                                continue;
+                            }
+                            (b'(', _) => depth += 1,
+                            (b')', _) => depth -= 1,
+                            _ => (),
+                        }
+                        output.push(input[read]);
+                        read += 1;
+                        if depth == 0 && output.last() == Some(&b')') {
+                            break;
+                        }
+                    }
+                }
+                // url, copied verbatim except for any padding inside the parens, and quotes which
+                // are redundant
+                b'u' | b'U'
+                    if input.len() > read + 4
+                        && input[read..read + 4].eq_ignore_ascii_case(b"url(")
+                        && output.last().is_none_or(|b| !is_ident_byte(*b)) =>
+                {
+                    trace!(self, &input, read, "url");
+                    let offset = self.offsets0[read];
+                    output.extend_from_slice(&input[read..read + 4]);
+                    read += 4;
+                    if read < len && input[read] == b' ' {
+                        read += 1;
+                    }
+                    let mut found_end = false;
+                    while read < len {
+                        match (input[read], self.quotes0.get(&read).copied()) {
+                            (b'\'' | b'"', Some(end)) => {
+                                let whole_url = end > read
+                                    && input[end] == input[read]
+                                    && input[end + 1..].iter().find(|b| **b != b' ') == Some(&b')');
+                                if whole_url && can_unquote_url(&input[read + 1..end]) {
+                                    output.extend_from_slice(&input[read + 1..end]);
+                                } else {
+                                    output.extend_from_slice(&input[read..=end]);
+                                }
+                                read = end + 1;
+                            }
+                            // a preserved comment, which may itself contain quotes or parens
+                            (b'/', Some(end)) => {
+                                output.extend_from_slice(&input[read..=end]);
+                                read = end + 1;
+                            }
+                            (b')', _) => {
+                                if output.last() == Some(&b' ') {
+                                    output.pop();
+                                }
+                                output.push(b')');
+                                read += 1;
+                                found_end = true;
+                                break;
+                            }
+                            (b, _) => {
+                                output.push(b);
+                                read += 1;
+                            }
+                        }
+                    }
+                    if !found_end {
+                        self.add_error_msg("reached end of input while inside url", offset);
+                    }
+                }
+                // important annotation, which is case-insensitive and may have a space after the
+                // `!`. Both are normalized.
+                b'!' if important_len(&input[read..]).is_some() => {
+                    trace!(self, &input, read, "important");
+                    // drop preceding space
+                    if let Some(last) = output.pop() {
+                        if last != b' ' {
+                            output.push(last);
+                        }
+                    }
+                    output.extend_from_slice(b"!important");
+                    read += important_len(&input[read..]).unwrap();
+                }
+
+                // possible hex color
+                b'#' if len > read + 3 => {
+                    trace!(self, &input, read, "hex color");
+                    peek = read + 1;
+                    while len > peek && input[peek].is_ascii_hexdigit() {
+                        peek += 1;
+                    }
+                    // a hex color must make up the whole token, e.g. `#defg` is not a color, and
+                    // only values hold colors, e.g. `#ABCDEF` may be a case-sensitive id selector,
+                    // including after a pseudo class as in `a:hover #ABCDEF{`
+                    let mut end = peek;
+                    while end < len && is_ident_byte(input[end]) {
+                        end += 1;
+                    }
+                    let color = end == peek
+                        && assignment.is_some_and(|a| {
+                            property_name(output[declaration_start..a].trim_ascii_end()).is_some()
+                        })
+                        && input[end..]
+                            .iter()
+                            .find(|b| [b'{', b';', b'}'].contains(*b))
+                            != Some(&b'{')
+                        && !self.options.no_color;
+                    if !color {
+                        output.extend_from_slice(&input[read..end]);
+                        read = end;
+                    } else if let Ok(hex_color) = try_minify_hex_color(&input[read..peek]) {
+                        let in_value =
+                            assignment.is_some() && !output[declaration_start..].starts_with(b"--");
+                        let mut hex_color = self.shorten_color(hex_color, in_value);
+                        self.count_color(&input[read..peek], &hex_color);
+                        output.append(&mut hex_color);
+                        read = peek;
+                    } else {
+                        output.push(input[read]);
+                        read += 1;
+                    }
+                }
+                // possible hsl func
+                b'h' if len > read + 9
+                    && (input[read + 1..=read + 3] == [b's', b'l', b'(']
+                        || input[read + 1..=read + 4] == [b's', b'l', b'a', b'(']) =>
+                {
+                    trace!(self, &input, read, "hsl func");
+                    peek = read + 4;
+                    if input[peek] == b'(' {
+                        peek += 1;
+                    }
+                    // letters are permitted for the unit of the hue
+                    while len > peek
+                        && input[peek] != b')'
+                        && (RGB_FUNC_DECODABLE.contains(&input[peek])
+                            || input[peek].is_ascii_alphabetic())
+                    {
+                        peek += 1
+                    }
+                    // bail if input ends inside the func
+                    if !self.options.no_color && peek < len && input[peek] == b')' {
+                        if let Ok(mut hex_color) =
+                            try_decode_hsl_func(&input[read..=peek], &self.options)
+                        {
+                            let in_value = assignment.is_some()
+                                && !output[declaration_start..].starts_with(b"--");
+                            hex_color = self
+                                .shorten_color(try_minify_hex_color(&hex_color).unwrap(), in_value);
+                            self.count_color(&input[read..=peek], &hex_color);
+                            output.append(&mut hex_color);
+                            read = peek + 1;
+                            continue;
+                        }
+                    }
+                    output.push(input[read]);
+                    read += 1;
+                }
+                // possible hwb func
+                b'h' if len > read + 9 && input[read + 1..=read + 3] == [b'w', b'b', b'('] => {
+                    trace!(self, &input, read, "hwb func");
+                    peek = read + 4;
+                    // letters are permitted for the unit of the hue
+                    while len > peek
+                        && input[peek] != b')'
+                        && (RGB_FUNC_DECODABLE.contains(&input[peek])
+                            || input[peek].is_ascii_alphabetic())
+                    {
+                        peek += 1
+                    }
+                    // bail if input ends inside the func
+                    if !self.options.no_color && peek < len && input[peek] == b')' {
+                        if let Ok(mut hex_color) =
+                            try_decode_hwb_func(&input[read..=peek], &self.options)
+                        {
+                            let in_value = assignment.is_some()
+                                && !output[declaration_start..].starts_with(b"--");
+                            hex_color = self
+                                .shorten_color(try_minify_hex_color(&hex_color).unwrap(), in_value);
+                            self.count_color(&input[read..=peek], &hex_color);
+                            output.append(&mut hex_color);
+                            read = peek + 1;
+                            continue;
+                        }
+                    }
+                    output.push(input[read]);
+                    read += 1;
+                }
+                // possible rgb func
+                b'r' if len > read + 9
+                    && (input[read + 1..=read + 3] == [b'g', b'b', b'(']
+                        || input[read + 1..=read + 4] == [b'g', b'b', b'a', b'(']) =>
+                {
+                    trace!(self, &input, read, "rgb func");
+                    peek = read + 4;
+                    if input[peek] == b'(' {
+                        peek += 1;
+                    }
+                    // a minus sign is permitted for a negative channel, which is clamped
+                    while len > peek
+                        && input[peek] != b')'
+                        && (RGB_FUNC_DECODABLE.contains(&input[peek]) || input[peek] == b'-')
+                    {
+                        peek += 1
+                    }
+                    // bail if input ends inside the func
+                    if !self.options.no_color && peek < len && input[peek] == b')' {
+                        if let Ok(mut hex_color) =
+                            try_decode_rgb_func(&input[read..=peek], &self.options)
+                        {
+                            let in_value = assignment.is_some()
+                                && !output[declaration_start..].starts_with(b"--");
+                            hex_color = self
+                                .shorten_color(try_minify_hex_color(&hex_color).unwrap(), in_value);
+                            self.count_color(&input[read..=peek], &hex_color);
+                            output.append(&mut hex_color);
+                            read = peek + 1;
+                            continue;
+                        }
+                    }
+                    output.push(input[read]);
+                    read += 1;
+                }
+                // possible oklab or oklch func
+                b'o' if len > read + 11
+                    && (input[read + 1..=read + 5] == *b"klab("
+                        || input[read + 1..=read + 5] == *b"klch(") =>
+                {
+                    trace!(self, &input, read, "oklab func");
+                    peek = read + 6;
+                    // letters are permitted for the unit of the hue, and minus signs for the axes
+                    while len > peek
+                        && input[peek] != b')'
+                        && (RGB_FUNC_DECODABLE.contains(&input[peek])
+                            || input[peek].is_ascii_alphabetic()
+                            || input[peek] == b'-')
+                    {
+                        peek += 1
+                    }
+                    // bail if input ends inside the func
+                    if !self.options.no_color && peek < len && input[peek] == b')' {
+                        if let Ok(hex_color) =
+                            try_decode_oklab_func(&input[read..=peek], &self.options)
+                        {
+                            let in_value = assignment.is_some()
+                                && !output[declaration_start..].starts_with(b"--");
+                            let mut hex_color = self
+                                .shorten_color(try_minify_hex_color(&hex_color).unwrap(), in_value);
+                            self.count_color(&input[read..=peek], &hex_color);
+                            output.append(&mut hex_color);
+                            read = peek + 1;
+                            continue;
+                        }
+                    }
+                    output.push(input[read]);
+                    read += 1;
+                }
+                // transparent, which is the same color as `#0000`, anywhere in a value which takes
+                // a color. Other values may have an identifier which happens to be `transparent`,
+                // e.g. the family name in `font-family:Transparent`, so are left alone
+                b't' | b'T'
+                    if !self.options.no_color
+                        && assignment.is_some_and(|a| {
+                            takes_color(&output[declaration_start..a], &output[a + 1..])
+                        })
+                        && !output[declaration_start..].starts_with(b"--")
+                        && output.last().is_none_or(|b| !is_ident_byte(*b))
+                        && input.len() >= read + TRANSPARENT.len()
+                        && input[read..read + TRANSPARENT.len()]
+                            .eq_ignore_ascii_case(TRANSPARENT)
+                        && input
+                            .get(read + TRANSPARENT.len())
+                            .is_none_or(|b| !is_ident_byte(*b) && *b != b'(')
+                        // not a pseudo class in a nested selector
+                        && input[read + TRANSPARENT.len()..]
+                            .iter()
+                            .find(|b| **b != b' ')
+                            != Some(&b'{') =>
+                {
+                    trace!(self, &input, read, "transparent");
+                    self.count_color(&input[read..read + TRANSPARENT.len()], b"#0000");
+                    output.extend_from_slice(b"#0000");
+                    read += TRANSPARENT.len();
+                }
+                // named color making up a whole value of a color property, which may be substituted
+                // with a shorter hex color. Other values may have a keyword which is also a color
+                // name, e.g. the animation-name in `animation:red 1s` or the family name in
+                // `font-family:Black`, so are left alone
+                b'a'..=b'z' | b'A'..=b'Z'
+                    if !self.options.no_color
+                        && assignment.is_some_and(|a| {
+                            a + 1 == output.len()
+                                && is_color_property(&output[declaration_start..a])
+                        }) =>
+                {
+                    trace!(self, &input, read, "named color");
+                    peek = read;
+                    while peek < len && is_ident_byte(input[peek]) {
+                        peek += 1;
+                    }
+                    // allow for an important annotation
+                    let end = match input.get(peek) {
+                        Some(b' ') => peek + 1,
+                        _ => peek,
+                    };
+                    let color = NAMED_COLORS
+                        .iter()
+                        .find(|(name, _)| name.eq_ignore_ascii_case(&input[read..peek]));
+                    match color {
+                        Some((_, hex))
+                            if end == len || [b';', b'}', b'!'].contains(&input[end]) =>
+                        {
+                            self.count_color(&input[read..peek], hex);
+                            output.extend_from_slice(hex);
+                            read = peek;
+                        }
+                        _ => {
+                            output.push(input[read]);
+                            read += 1;
+                        }
+                    }
+                }
+                // number in a value, which may be rounded or have a redundant zero or unit removed
+                b'0'..=b'9' | b'.'
+                    if assignment.is_some()
+                        && !output[declaration_start..].starts_with(b"--")
+                        && can_start_number(&output)
+                        && input[read..].iter().take(2).any(|b| b.is_ascii_digit()) =>
+                {
+                    trace!(self, &input, read, "number");
+                    peek = read;
+                    while peek < len && (input[peek].is_ascii_digit() || input[peek] == b'.') {
+                        peek += 1;
+                    }
+                    let number = &input[read..peek];
+                    // leave anything with an exponent, or which isn't a simple decimal, alone
+                    let exponent = peek + 1 < len
+                        && [b'e', b'E'].contains(&input[peek])
+                        && (input[peek + 1].is_ascii_digit()
+                            || [b'-', b'+'].contains(&input[peek + 1])
+                                && input.get(peek + 2).is_some_and(|b| b.is_ascii_digit()));
+                    let mut unit_end = peek;
+                    while unit_end < len
+                        && (input[unit_end].is_ascii_alphabetic() || input[unit_end] == b'%')
+                    {
+                        unit_end += 1;
+                    }
+                    // a zero length is a zero whatever its unit, except inside functions such as
+                    // calc() where a unitless zero may be invalid, and in flex where a unitless
+                    // zero is the flex-grow rather than the flex-basis
+                    if parens == 0
+                        && is_zero_length(&input[read..unit_end], false)
+                        && !property_name(&output[declaration_start..assignment.unwrap()])
+                            .is_some_and(|p| {
+                                p.len() >= 4 && p[p.len() - 4..].eq_ignore_ascii_case(b"flex")
+                            })
+                    {
+                        output.push(b'0');
+                        read = unit_end;
+                        continue;
+                    }
+                    let mut number = match self.options.round_decimals {
+                        Some(precision) if !exponent => {
+                            round_decimal(number, precision).unwrap_or(number.to_vec())
+                        }
+                        _ => number.to_vec(),
+                    };
+                    // the leading zero of a fraction is redundant, e.g. `0.5` is `.5`
+                    if number.len() > 2 && number.starts_with(b"0.") {
+                        number.remove(0);
+                    }
+                    output.append(&mut number);
+                    read = peek;
+                }
+                // all else copy verbatim
+                _ => {
+                    trace!(self, &input, read, "copy");
+                    output.push(input[read]);
+                    read += 1;
+                }
+            }
+        }
+        self.output1 = output;
+    }
+
+    // called with a declaration up to and including its ':' at the tail of output, true if its
+    // value should be copied as it is
+    fn preserves(&self, declaration: &[u8]) -> bool {
+        let property = &declaration[..declaration.len() - 1];
+        let property = property.strip_suffix(b" ").unwrap_or(property);
+        let Some(property) = property_name(property) else {
+            return false;
+        };
+        self.options.verbatim.iter().any(|prefix| {
+            property.len() >= prefix.len()
+                && property[..prefix.len()].eq_ignore_ascii_case(prefix.as_bytes())
+        }) || self
+            .preserve
+            .is_some_and(|preserve| std::str::from_utf8(property).is_ok_and(preserve))
+    }
+
+    // true if the declaration from start to the end of output is identical to the previous one, and
+    // dedupe_declarations is set. Only adjacent declarations are compared, as removing one which is
+    // separated from its duplicate by another declaration could change which of them wins.
+    fn is_duplicate(&self, output: &[u8], start: usize, previous: Option<(usize, usize)>) -> bool {
+        self.options.dedupe_declarations
+            && start < output.len()
+            && previous.is_some_and(|(p_start, p_end)| output[p_start..p_end] == output[start..])
+    }
+
+    // called with the complete declaration at the tail of output, from start up to but excluding
+    // the ';' or '}' that terminates it, and with the index of its ':' assignment operator and the
+    // offset of the ':' in the input. Returns true if the declaration was removed.
+    fn finish_declaration(
+        &mut self,
+        output: &mut Vec<u8>,
+        start: usize,
+        assignment: usize,
+        offset: usize,
+    ) -> bool {
+        if start == assignment {
+            self.add_error_msg("declaration with empty property name", offset);
+            return false;
+        }
+        let Some(property) = property_name(&output[start..assignment]) else {
+            return false;
+        };
+        // an empty custom property is valid, as it sets the property to an empty value
+        if self.options.strip_empty_declarations
+            && assignment + 1 == output.len()
+            && !property.starts_with(b"--")
+        {
+            output.truncate(start);
+            return true;
+        }
+        let box_shorthand = BOX_SHORTHANDS
+            .iter()
+            .any(|p| p.eq_ignore_ascii_case(property));
+        let font_weight = property.eq_ignore_ascii_case(b"font-weight");
+        // margins and paddings are resolved against the width of the containing block, which is
+        // always definite, so a zero percentage is a zero there
+        let lowercase = property.to_ascii_lowercase();
+        let percent = lowercase.starts_with(b"margin") || lowercase.starts_with(b"padding");
+        if self.options.collapse_zeros
+            && box_shorthand
+            && output[assignment + 1..]
+                .split(|b| *b == b' ')
+                .all(|v| is_zero_length(v, percent))
+        {
+            output.truncate(assignment + 1);
+            output.push(b'0');
+        }
+        if self.options.collapse_shorthands && box_shorthand {
+            if let Some(collapsed) = collapse_box_values(&output[assignment + 1..]) {
+                output.truncate(assignment + 1);
+                output.extend_from_slice(&collapsed);
+            }
+        }
+        // other properties take keywords such as `normal` meaning something else entirely
+        if font_weight && !self.preserves(&output[start..=assignment]) {
+            let value = &output[assignment + 1..];
+            // an important annotation is kept as it is
+            let end = value.iter().position(|b| *b == b'!').unwrap_or(value.len());
+            let weight = FONT_WEIGHTS
+                .iter()
+                .find(|(keyword, _)| keyword.eq_ignore_ascii_case(&value[..end]));
+            if let Some((_, number)) = weight {
+                output.splice(assignment + 1..assignment + 1 + end, number.iter().copied());
+            }
+        }
+        false
+    }
+}
+
+// returns the property name if input is a plausible property name, i.e. not a selector or the
+// prelude of an at-rule that happens to contain a ':'
+fn property_name(input: &[u8]) -> Option<&[u8]> {
+    if input.is_empty()
+        || !input
+            .iter()
+            .all(|b| b.is_ascii_alphanumeric() || *b == b'-' || *b == b'_')
+    {
+        return None;
+    }
+    Some(input)
+}
+
+// folds the value of a box shorthand whose sides repeat according to the rule for 1-4 values,
+// e.g. `1px 2px 1px 2px` becomes `1px 2px`, or returns None if it isn't a list of simple tokens or
+// can't be folded
+fn collapse_box_values(value: &[u8]) -> Option<Vec<u8>> {
+    // an important annotation is kept as it is
+    let end = value.iter().position(|b| *b == b'!').unwrap_or(value.len());
+    let mut values: Vec<&[u8]> = value[..end].split(|b| *b == b' ').collect();
+    if values.iter().any(|v| {
+        v.is_empty()
+            || v.iter()
+                .any(|b| [b'(', b')', b'/', b',', b'"', b'\''].contains(b))
+    }) {
+        return None;
+    }
+    let len = values.len();
+    if len == 4 && values[3] == values[1] {
+        values.pop();
+    }
+    if values.len() == 3 && values[2] == values[0] {
+        values.pop();
+    }
+    if values.len() == 2 && values[1] == values[0] {
+        values.pop();
+    }
+    if values.len() == len {
+        return None;
+    }
+    let mut collapsed = values.join(&b' ');
+    collapsed.extend_from_slice(&value[end..]);
+    Some(collapsed)
+}
+
+// true if a number appended to output would be a token of its own, rather than part of an
+// identifier, allowing for a sign
+fn can_start_number(output: &[u8]) -> bool {
+    let output = match output.last() {
+        Some(b'-' | b'+') => &output[..output.len() - 1],
+        _ => output,
+    };
+    output.last().is_none_or(|b| !is_ident_byte(*b))
+}
+
+// rounds a decimal number half up to at most precision fractional digits, removing trailing
+// zeros, or returns None if it isn't a decimal number or needs no rounding
+fn round_decimal(input: &[u8], precision: usize) -> Option<Vec<u8>> {
+    let point = input.iter().position(|b| *b == b'.')?;
+    if input.len() - point - 1 <= precision
+        || point + 1 == input.len()
+        || !input.iter().all(|b| b.is_ascii_digit() || *b == b'.')
+        || input.iter().filter(|b| **b == b'.').count() > 1
+    {
+        return None;
+    }
+    // rounding is done on the digits so as to avoid floating point error
+    let mut digits: Vec<u8> = input[..point]
+        .iter()
+        .chain(&input[point + 1..=point + precision])
+        .copied()
+        .collect();
+    let mut int_len = point;
+    if input[point + precision + 1] >= b'5' {
+        let mut carry = true;
+        for d in digits.iter_mut().rev() {
+            if *d == b'9' {
+                *d = b'0';
+            } else {
+                *d += 1;
+                carry = false;
+                break;
+            }
+        }
+        if carry {
+            digits.insert(0, b'1');
+            int_len += 1;
+        }
+    }
+    let mut rounded = digits[..int_len].to_vec();
+    let mut fraction = &digits[int_len..];
+    while let Some(f) = fraction.strip_suffix(b"0") {
+        fraction = f;
+    }
+    if !fraction.is_empty() {
+        rounded.push(b'.');
+        rounded.extend_from_slice(fraction);
+    } else if rounded.is_empty() {
+        rounded.push(b'0');
+    }
+    Some(rounded)
+}
+
+/*
+ * the attribute selector starting at the '[' at read, with whitespace removed and the quotes
+ * removed from a value which is an identifier, and the index of its ']', or None if it isn't a
+ * simple attribute selector
+ */
+fn minify_attribute_selector(
+    input: &[u8],
+    read: usize,
+    quotes: &HashMap<usize, usize>,
+) -> Option<(Vec<u8>, usize)> {
+    let skip_space = |i: usize| {
+        if input.get(i) == Some(&b' ') {
+            i + 1
+        } else {
+            i
+        }
+    };
+    let mut v = vec![b'['];
+    let mut i = skip_space(read + 1);
+    // the attribute name, which may have a namespace prefix, e.g. `*|href`
+    let start = i;
+    while input.get(i).is_some_and(|b| {
+        is_ident_byte(*b) || [b'|', b'*'].contains(b) && input.get(i + 1) != Some(&b'=')
+    }) {
+        i += 1;
+    }
+    if !input[start..i].iter().any(|b| is_ident_byte(*b)) {
+        return None;
+    }
+    v.extend_from_slice(&input[start..i]);
+    i = skip_space(i);
+    if input.get(i) == Some(&b']') {
+        v.push(b']');
+        return Some((v, i));
+    }
+    // the operator
+    let operator = match input.get(i)? {
+        b'=' => 1,
+        b'~' | b'|' | b'^' | b'$' | b'*' if input.get(i + 1) == Some(&b'=') => 2,
+        _ => return None,
+    };
+    v.extend_from_slice(&input[i..i + operator]);
+    i = skip_space(i + operator);
+    // the value, which must be quoted unless it's an identifier
+    let unquoted = match quotes.get(&i) {
+        Some(&end) if end > i && input[end] == input[i] => {
+            let content = &input[i + 1..end];
+            let unquoted = is_identifier(content);
+            if unquoted {
+                v.extend_from_slice(content);
+            } else {
+                v.extend_from_slice(&input[i..=end]);
+            }
+            i = end + 1;
+            unquoted
+        }
+        Some(_) => return None,
+        None => {
+            let start = i;
+            while input.get(i).is_some_and(|b| is_ident_byte(*b)) {
+                i += 1;
+            }
+            if start == i {
+                return None;
+            }
+            v.extend_from_slice(&input[start..i]);
+            true
+        }
+    };
+    i = skip_space(i);
+    // a case sensitivity modifier, which must be separated from an unquoted value
+    if input.get(i).is_some_and(|b| b"iIsS".contains(b))
+        && input.get(skip_space(i + 1)) == Some(&b']')
+    {
+        if unquoted {
+            v.push(b' ');
+        }
+        v.push(input[i]);
+        i = skip_space(i + 1);
+    }
+    if input.get(i) != Some(&b']') {
+        return None;
+    }
+    v.push(b']');
+    Some((v, i))
+}
+
+/*
+ * true if input is a css identifier, and so may be written without quotes. A leading `--` or an
+ * escape is treated as not being an identifier, to be safe.
+ */
+fn is_identifier(input: &[u8]) -> bool {
+    let name = input.strip_prefix(b"-").unwrap_or(input);
+    name.first()
+        .is_some_and(|b| b.is_ascii_alphabetic() || *b == b'_' || *b >= 0x80)
+        && name.iter().all(|b| is_ident_byte(*b))
+}
+
+/*
+ * true if a rule with this selector or at-rule prelude has no effect when its block is empty.
+ * Other at-rules such as `@layer` and `@keyframes` can have an effect even when empty, and a
+ * preserved comment in the prelude would be lost.
+ */
+fn is_removable_when_empty(prelude: &[u8]) -> bool {
+    if prelude.windows(2).any(|w| w == b"/*") {
+        return false;
+    }
+    match prelude.first() {
+        Some(b'@') => [&b"@media"[..], b"@supports", b"@container"]
+            .iter()
+            .any(|r| {
+                prelude.len() > r.len()
+                    && prelude[..r.len()].eq_ignore_ascii_case(r)
+                    && !is_ident_byte(prelude[r.len()])
+            }),
+        _ => true,
+    }
+}
+
+/*
+ * true if the content of a quoted url can be written without quotes, i.e. it isn't empty or a data
+ * uri and has no whitespace, parens, quotes, backslashes or control chars
+ */
+fn can_unquote_url(url: &[u8]) -> bool {
+    !url.is_empty()
+        && url
+            .get(..5)
+            .is_none_or(|scheme| !scheme.eq_ignore_ascii_case(b"data:"))
+        && url.iter().all(|b| {
+            !b.is_ascii_whitespace()
+                && !b.is_ascii_control()
+                && ![b'(', b')', b'\'', b'"', b'\\'].contains(b)
+        })
+}
+
+/*
+ * the length of the important annotation at the start of input, if there is one
+ */
+fn important_len(input: &[u8]) -> Option<usize> {
+    let start = match input.get(1) {
+        Some(b' ') => 2,
+        _ => 1,
+    };
+    let end = start + b"important".len();
+    if input.len() >= end
+        && input[start..end].eq_ignore_ascii_case(b"important")
+        && input.get(end).is_none_or(|b| !is_ident_byte(*b))
+    {
+        Some(end)
+    } else {
+        None
+    }
+}
+
+// true if the value of `property` may be just a color
+fn is_color_property(property: &[u8]) -> bool {
+    let lowercase = property.to_ascii_lowercase();
+    lowercase.ends_with(b"-color") || COLOR_PROPERTIES.contains(&lowercase.as_slice())
+}
+
+// true if the value of `property` may have a color anywhere in it, as for a color property, a
+// shadow, or the argument of a gradient or `drop-shadow()` in the value so far
+fn takes_color(property: &[u8], value: &[u8]) -> bool {
+    let lowercase = property.to_ascii_lowercase();
+    is_color_property(property)
+        || lowercase.starts_with(b"background")
+        || lowercase.starts_with(b"border")
+        || lowercase.ends_with(b"-shadow")
+        || value
+            .windows(b"gradient(".len())
+            .any(|w| w.eq_ignore_ascii_case(b"gradient("))
+        || value
+            .windows(b"shadow(".len())
+            .any(|w| w.eq_ignore_ascii_case(b"shadow("))
+}
+
+// matches a zero numeric literal with an optional sign and an optional length unit, e.g. `0`,
+// `-0.0`, `0px` or `.0em`, and also a zero percentage, e.g. `0%`, if percent is set
+fn is_zero_length(input: &[u8], percent: bool) -> bool {
+    let mut read = 0;
+    if read < input.len() && [b'-', b'+'].contains(&input[read]) {
+        read += 1;
+    }
+    let mut digits = 0;
+    let mut point = false;
+    while read < input.len() {
+        match input[read] {
+            b'0' => digits += 1,
+            b'.' if !point => point = true,
+            _ => break,
+        }
+        read += 1;
+    }
+    let unit = &input[read..];
+    digits > 0
+        && (unit.is_empty()
+            || (percent && unit == b"%")
+            || LENGTH_UNITS.iter().any(|u| u.eq_ignore_ascii_case(unit)))
+}
+
+/*
+ * requires input to start with "hsl(" or "hsla(" and end with ")"
+ */
+fn try_decode_hsl_func(input: &[u8], options: &MinifyOptions) -> Result<Vec<u8>, ()> {
+    let mut v = vec![b'#'];
+    let mut read = 3;
+    if input[read] == b'a' {
+        read += 1;
+    }
+    if input[read] != b'(' {
+        return Err(());
+    }
+    read += 1;
+    // skip padding inside the parens
+    while input[read] == b' ' {
+        read += 1;
+    }
+    let mut hsla_d = [
+        String::with_capacity(10),
+        String::with_capacity(10),
+        String::with_capacity(10),
+        String::with_capacity(10),
+    ];
+    let mut percents = [false, false, false, false];
+    let mut i = 0;
+
+    while input[read] != b')' {
+        match input[read] {
+            // the unit of the hue, and anything else invalid in it, is left to try_decode_hue
+            u if i == 0 && (u.is_ascii_alphabetic() || u == b'%') => hsla_d[0].push(char::from(u)),
+            x if !RGB_FUNC_DECODABLE.contains(&x) => return Err(()),
+            d if d.is_ascii_digit() || d == b'.' => hsla_d[i].push(char::from(d)),
+            b'%' => percents[i] = true,
+            b' ' | b',' | b'/' => {
+                while [b' ', b',', b'/'].contains(&input[read + 1]) {
+                    read += 1;
+                }
+                if input[read + 1] != b')' {
+                    i += 1;
+                }
+                // too many arguments
+                if i > 3 {
+                    return Err(());
+                }
+            }
+            _ => unreachable!(), // did we add chars to RGB_FUNC_DECODABLE and not match here?
+        }
+        read += 1;
+    }
+
+    // check we got required input for h, s, l
+    for digits in &hsla_d[0..=2] {
+        if digits.is_empty() {
+            return Err(());
+        }
+    }
+
+    let h = try_decode_hue(&hsla_d[0])?;
+    let s = f32::from_str(&hsla_d[1]).or(Err(()))? / 100.0;
+    if !(0.0..=1.0).contains(&s) {
+        return Err(());
+    }
+    let l = f32::from_str(&hsla_d[2]).or(Err(()))? / 100.0;
+    if !(0.0..=1.0).contains(&l) {
+        return Err(());
+    }
+
+    // weird algorithm from wikipedia...
+    let a = s * {
+        if l <= 0.5 {
+            l
+        } else {
+            1_f32 - l
+        }
+    };
+    let ks = [
+        (h / 30_f32) % 12_f32,
+        (8_f32 + h / 30_f32) % 12_f32,
+        (4_f32 + h / 30_f32) % 12_f32,
+    ];
+    for k in ks {
+        let c = match k {
+            ..=2_f32 => -1_f32,
+            2_f32..=4_f32 => k - 3_f32,
+            4_f32..=8_f32 => 1_f32,
+            8_f32..=10_f32 => 9_f32 - k,
+            10_f32.. => -1_f32,
+            _ => unreachable!(),
+        };
+        let byte = try_round_channel((l - a * c) * 255_f32, options)?;
+        let hex = format!("{:04x}", byte).into_bytes();
+        //igore leading '0x' get only the actual hexadecimal digits
+        v.push(hex[2]);
+        v.push(hex[3]);
+    }
+
+    // alpha channel
+    if let Some(byte) = try_decode_alpha(&hsla_d[3], percents[3], options)? {
+        //format as hexadecimal
+        let hex = format!("{:04x}", byte).into_bytes();
+        //igore leading '0x' get only the actual hexadecimal digits
+        v.push(hex[2]);
+        v.push(hex[3]);
+    }
+    Ok(v)
+}
+
+/*
+ * requires input to start with "hwb(" and end with ")"
+ */
+fn try_decode_hwb_func(input: &[u8], options: &MinifyOptions) -> Result<Vec<u8>, ()> {
+    let mut v = vec![b'#'];
+    let mut read = 3;
+    if input[read] != b'(' {
+        return Err(());
+    }
+    read += 1;
+    // skip padding inside the parens
+    while input[read] == b' ' {
+        read += 1;
+    }
+    let mut hwba_d = [
+        String::with_capacity(10),
+        String::with_capacity(10),
+        String::with_capacity(10),
+        String::with_capacity(10),
+    ];
+    let mut percents = [false, false, false, false];
+    let mut i = 0;
+
+    while input[read] != b')' {
+        match input[read] {
+            // the unit of the hue, and anything else invalid in it, is left to try_decode_hue
+            u if i == 0 && (u.is_ascii_alphabetic() || u == b'%') => hwba_d[0].push(char::from(u)),
+            x if !RGB_FUNC_DECODABLE.contains(&x) => return Err(()),
+            d if d.is_ascii_digit() || d == b'.' => hwba_d[i].push(char::from(d)),
+            b'%' => percents[i] = true,
+            b' ' | b',' | b'/' => {
+                while [b' ', b',', b'/'].contains(&input[read + 1]) {
+                    read += 1;
+                }
+                if input[read + 1] != b')' {
+                    i += 1;
+                }
+                // too many arguments
+                if i > 3 {
+                    return Err(());
+                }
+            }
+            _ => unreachable!(), // did we add chars to RGB_FUNC_DECODABLE and not match here?
+        }
+        read += 1;
+    }
+
+    // check we got required input for h, w, b
+    for digits in &hwba_d[0..=2] {
+        if digits.is_empty() {
+            return Err(());
+        }
+    }
+
+    let h = try_decode_hue(&hwba_d[0])?;
+    let mut w = f32::from_str(&hwba_d[1]).or(Err(()))? / 100.0;
+    let mut b = f32::from_str(&hwba_d[2]).or(Err(()))? / 100.0;
+    if !(0.0..=1.0).contains(&w) || !(0.0..=1.0).contains(&b) {
+        return Err(());
+    }
+    // whiteness and blackness adding up to more than 100% are normalised to a grey
+    if w + b > 1.0 {
+        let sum = w + b;
+        w /= sum;
+        b /= sum;
+    }
+
+    // the fully saturated hue, as for hsl() with a lightness of 50%, mixed with white and black
+    let ks = [
+        (h / 30_f32) % 12_f32,
+        (8_f32 + h / 30_f32) % 12_f32,
+        (4_f32 + h / 30_f32) % 12_f32,
+    ];
+    for k in ks {
+        let c = match k {
+            ..=2_f32 => -1_f32,
+            2_f32..=4_f32 => k - 3_f32,
+            4_f32..=8_f32 => 1_f32,
+            8_f32..=10_f32 => 9_f32 - k,
+            10_f32.. => -1_f32,
+            _ => unreachable!(),
+        };
+        let hue = 0.5 - 0.5 * c;
+        let byte = try_round_channel((hue * (1.0 - w - b) + w) * 255_f32, options)?;
+        let hex = format!("{:04x}", byte).into_bytes();
+        //igore leading '0x' get only the actual hexadecimal digits
+        v.push(hex[2]);
+        v.push(hex[3]);
+    }
+
+    // alpha channel
+    if let Some(byte) = try_decode_alpha(&hwba_d[3], percents[3], options)? {
+        //format as hexadecimal
+        let hex = format!("{:04x}", byte).into_bytes();
+        //igore leading '0x' get only the actual hexadecimal digits
+        v.push(hex[2]);
+        v.push(hex[3]);
+    }
+    Ok(v)
+}
+
+/*
+ * requires input to start with "oklab(" or "oklch(" and end with ")"
+ */
+fn try_decode_oklab_func(input: &[u8], options: &MinifyOptions) -> Result<Vec<u8>, ()> {
+    let mut v = vec![b'#'];
+    // oklch() gives chroma and hue in place of the a and b axes
+    let polar = input[4] == b'h';
+    let mut read = 5;
+    if input[read] != b'(' {
+        return Err(());
+    }
+    read += 1;
+    // skip padding inside the parens
+    while input[read] == b' ' {
+        read += 1;
+    }
+    let mut lab_d = [
+        String::with_capacity(10),
+        String::with_capacity(10),
+        String::with_capacity(10),
+        String::with_capacity(10),
+    ];
+    let mut percents = [false, false, false, false];
+    let mut i = 0;
+
+    // only the modern syntax without commas is valid
+    while input[read] != b')' {
+        match input[read] {
+            // the unit of the hue, and anything else invalid in it, is left to try_decode_hue
+            u if polar && i == 2 && (u.is_ascii_alphabetic() || u == b'%') => {
+                lab_d[2].push(char::from(u))
+            }
+            d if d.is_ascii_digit() || d == b'.' || d == b'-' => lab_d[i].push(char::from(d)),
+            b'%' => percents[i] = true,
+            b' ' | b'/' => {
+                while [b' ', b'/'].contains(&input[read + 1]) {
+                    read += 1;
+                }
+                if input[read + 1] != b')' {
+                    i += 1;
+                }
+                // too many arguments
+                if i > 3 {
+                    return Err(());
+                }
+            }
+            _ => return Err(()),
+        }
+        read += 1;
+    }
+
+    // check we got required input for l, a, b or l, c, h
+    for digits in &lab_d[0..=2] {
+        if digits.is_empty() {
+            return Err(());
+        }
+    }
+
+    let mut l = f64::from_str(&lab_d[0]).or(Err(()))?;
+    if percents[0] {
+        l /= 100.0;
+    }
+    if !(0.0..=1.0).contains(&l) {
+        return Err(());
+    }
+    // 100% of chroma or of either axis is 0.4
+    let mut x = f64::from_str(&lab_d[1]).or(Err(()))?;
+    if percents[1] {
+        x *= 0.004;
+    }
+    let (a, b) = if polar {
+        if x < 0.0 || percents[2] {
+            return Err(());
+        }
+        let h = f64::from(try_decode_hue(&lab_d[2])?).to_radians();
+        (x * h.cos(), x * h.sin())
+    } else {
+        let mut y = f64::from_str(&lab_d[2]).or(Err(()))?;
+        if percents[2] {
+            y *= 0.004;
+        }
+        (x, y)
+    };
+
+    // oklab to linear srgb, by way of the cone responses
+    let lms = [
+        (l + 0.3963377774 * a + 0.2158037573 * b).powi(3),
+        (l - 0.1055613458 * a - 0.0638541728 * b).powi(3),
+        (l - 0.0894841775 * a - 1.2914855480 * b).powi(3),
+    ];
+    let rgb = [
+        4.0767416621 * lms[0] - 3.3077115913 * lms[1] + 0.2309699292 * lms[2],
+        -1.2684380046 * lms[0] + 2.6097574011 * lms[1] - 0.3413193965 * lms[2],
+        -0.0041960863 * lms[0] - 0.7034186147 * lms[1] + 1.7076147010 * lms[2],
+    ];
+    for linear in rgb {
+        let gamma = if linear <= 0.0031308 {
+            12.92 * linear
+        } else {
+            1.055 * linear.powf(1.0 / 2.4) - 0.055
+        };
+        // a color outside the srgb gamut rounds to a channel outside 0-255, which fails here
+        // rather than being clamped to a visibly different color
+        let byte = try_round_channel((gamma * 255.0) as f32, options)?;
+        let hex = format!("{:04x}", byte).into_bytes();
+        //igore leading '0x' get only the actual hexadecimal digits
+        v.push(hex[2]);
+        v.push(hex[3]);
+    }
+
+    // alpha channel
+    if let Some(byte) = try_decode_alpha(&lab_d[3], percents[3], options)? {
+        //format as hexadecimal
+        let hex = format!("{:04x}", byte).into_bytes();
+        //igore leading '0x' get only the actual hexadecimal digits
+        v.push(hex[2]);
+        v.push(hex[3]);
+    }
+    Ok(v)
+}
+
+/*
+ * decodes a hue, which is a number followed by an optional angle unit, to degrees
+ */
+fn try_decode_hue(hue: &str) -> Result<f32, ()> {
+    let split = hue
+        .find(|c: char| !c.is_ascii_digit() && c != '.' && c != '-')
+        .unwrap_or(hue.len());
+    let (digits, unit) = hue.split_at(split);
+    let h = f32::from_str(digits).or(Err(()))?;
+    let h = match unit.to_ascii_lowercase().as_str() {
+        "" | "deg" => h,
+        "grad" => h * 0.9,
+        "rad" => h.to_degrees(),
+        "turn" => h * 360.0,
+        _ => return Err(()),
+    };
+    if !(0.0..=360.0).contains(&h) {
+        return Err(());
+    }
+    Ok(h)
+}
+
+/*
+ * requires input to start with "rgb(" or "rgba(" and end with ")"
+ */
+fn try_decode_rgb_func(input: &[u8], options: &MinifyOptions) -> Result<Vec<u8>, ()> {
+    let mut v = vec![b'#'];
+    let mut read = 3;
+    if input[read] == b'a' {
+        read += 1;
+    }
+    if input[read] != b'(' {
+        return Err(());
+    }
+    read += 1;
+    // skip padding inside the parens
+    while input[read] == b' ' {
+        read += 1;
+    }
+    let mut rgba_d = [
+        String::with_capacity(10),
+        String::with_capacity(10),
+        String::with_capacity(10),
+        String::with_capacity(10),
+    ];
+    let mut percents = [false, false, false, false];
+    let mut i = 0;
+    while input[read] != b')' {
+        match input[read] {
+            // a negative channel is clamped to zero
+            b'-' => rgba_d[i].push('-'),
+            x if !RGB_FUNC_DECODABLE.contains(&x) => return Err(()),
+            d if d.is_ascii_digit() || d == b'.' => rgba_d[i].push(char::from(d)),
+            b'%' => percents[i] = true,
+            b' ' | b',' | b'/' => {
+                while [b' ', b',', b'/'].contains(&input[read + 1]) {
+                    read += 1;
+                }
+                if input[read + 1] != b')' {
+                    i += 1;
+                }
+                // too many arguments
+                if i > 3 {
+                    return Err(());
+                }
+            }
+            _ => unreachable!(), // did we add chars to RGB_FUNC_DECODABLE and not match here?
+        }
+        read += 1;
+    }
+    // check we got required input for r, g, b
+    for i in 0..=2 {
+        if rgba_d[i].is_empty() {
+            return Err(());
+        }
+        // browsers clamp channels which are out of range, so we do too
+        let byte: u8 = if percents[i] {
+            let decimal = f32::from_str(&rgba_d[i]).or(Err(()))?; // 👈 #unexpectedlisp
+            try_round_channel(decimal.clamp(0_f32, 100_f32) * 255_f32 / 100_f32, options)?
+        } else {
+            let integer = i32::from_str(&rgba_d[i]).or(Err(()))?;
+            integer.clamp(u8::MIN.into(), u8::MAX.into()) as u8
+        };
+        //format as hexadecimal
+        let hex = format!("{:04x}", byte).into_bytes();
+        //igore leading '0x' get only the actual hexadecimal digits
+        v.push(hex[2]);
+        v.push(hex[3]);
+    }
+    // alpha channel
+    if let Some(byte) = try_decode_alpha(&rgba_d[3], percents[3], options)? {
+        //format as hexadecimal
+        let hex = format!("{:04x}", byte).into_bytes();
+        //igore leading '0x' get only the actual hexadecimal digits
+        v.push(hex[2]);
+        v.push(hex[3]);
+    }
+    Ok(v)
+}
+
+/*
+ * rounds a color channel to a byte, failing if it's out of range or if rounding would change it by
+ * more than the max_color_error option permits
+ */
+fn try_round_channel(exact: f32, options: &MinifyOptions) -> Result<u8, ()> {
+    let integer = exact.round();
+    if integer < u8::MIN.into() || integer > u8::MAX.into() {
+        return Err(());
+    }
+    if options
+        .max_color_error
+        .is_some_and(|max| (exact - integer).abs() > max.max(BYTE_EPSILON))
+    {
+        return Err(());
+    }
+    Ok(unsafe { integer.to_int_unchecked() })
+}
+
+/*
+ * decodes the digits of an alpha channel to a byte, or None if there is no alpha channel or it is
+ * fully opaque and can be omitted
+ */
+fn try_decode_alpha(
+    digits: &str,
+    percent: bool,
+    options: &MinifyOptions,
+) -> Result<Option<u8>, ()> {
+    if digits.is_empty() || ["1", "1.0", "100"].contains(&digits) {
+        return Ok(None);
+    }
+    let decimal = f32::from_str(digits).or(Err(()))?;
+    let exact = if percent {
+        decimal * 255_f32 / 100_f32
+    } else {
+        decimal * 255_f32
+    };
+    let integer = exact.round();
+    if integer < u8::MIN.into() || integer > u8::MAX.into() {
+        return Err(());
+    }
+    // an 8 bit alpha can't always exactly represent the source alpha
+    if options.lossless_alpha && (exact - integer).abs() > BYTE_EPSILON {
+        return Err(());
+    }
+    Ok(Some(unsafe { integer.to_int_unchecked() }))
+}
+
+fn try_minify_hex_color(input: &[u8]) -> Result<Vec<u8>, ()> {
+    let len = input.len();
+    if ![4, 5, 7, 9].contains(&len) || input[0] != b'#' {
+        return Err(());
+    }
+    let mut v = vec![b'#'];
+    // lowercase hex digits compress better, and are the same color
+    for byte in &input[1..] {
+        if !byte.is_ascii_hexdigit() {
+            return Err(());
+        }
+        v.push(byte.to_ascii_lowercase());
+    }
+    if len == 9 && v[1] == v[2] && v[3] == v[4] && v[5] == v[6] && v[7] == v[8] {
+        v.remove(8);
+        v.remove(6);
+        v.remove(4);
+        v.remove(2);
+    }
+    if len == 7 && v[1] == v[2] && v[3] == v[4] && v[5] == v[6] {
+        v.remove(6);
+        v.remove(4);
+        v.remove(2);
+    }
+    Ok(v)
+}
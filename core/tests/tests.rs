@@ -0,0 +1,243 @@
+#[cfg(test)]
+mod tests {
+    use const_css_minify_core::{
+        minify_str, minify_with_predicate, minify_with_stats, Minifier, MinifyOptions, MinifyStats,
+        TransformStats,
+    };
+
+    #[test]
+    fn runtime_minify() {
+        assert_eq!(
+            minify_str("div { margin : 0px  auto ; color: rgb(255, 255, 255) }"),
+            "div{margin:0 auto;color:#fff}"
+        );
+        assert_eq!(minify_str(""), "");
+    }
+
+    #[test]
+    fn transform_stats() {
+        let (css, stats) = minify_with_stats(
+            "/* header */ a { color: #ffffff; background: rgb(0, 0, 0); } \
+            b { color: #abc; border-color: white; /**/ }",
+        );
+        assert_eq!(
+            css,
+            "a{color:#fff;background:#000}b{color:#abc;border-color:#fff}"
+        );
+        assert_eq!(
+            stats,
+            TransformStats {
+                colors: 3,
+                color_bytes_saved: 3 + 8 + 1,
+                comments: 2,
+                comment_bytes_saved: 12 + 4,
+                semicolons: 2,
+            }
+        );
+        assert_eq!(minify_with_stats("").1, TransformStats::default());
+    }
+
+    #[test]
+    fn preserve_predicate() {
+        let preserve = |property: &str| property == "grid-template-areas";
+        assert_eq!(
+            minify_with_predicate(
+                r#"
+                .grid {
+                    grid-template-areas:
+                        "header header"
+                        "sidebar main" ;
+                    margin : 0px  0px ;
+                    color: rgb(255, 255, 255);
+                }
+                "#,
+                preserve
+            ),
+            r#".grid{grid-template-areas:"header header" "sidebar main";margin:0 0;color:#fff}"#
+        );
+        // preserved values are copied without minifying colors
+        assert_eq!(
+            minify_with_predicate("a { color: #ffffff; background: #ffffff }", |p| p
+                == "color"),
+            "a{color:#ffffff;background:#fff}"
+        );
+        // nested rules are not mistaken for preserved declarations
+        assert_eq!(
+            minify_with_predicate("a { b:hover { color: #ffffff } }", |p| p == "b"),
+            "a{b:hover{color:#fff}}"
+        );
+        assert_eq!(
+            minify_with_predicate("a { color: #ffffff }", |_| false),
+            "a{color:#fff}"
+        );
+    }
+
+    #[test]
+    fn byte_order_mark() {
+        assert_eq!(
+            minify_with_predicate("\u{feff} a { b: c }", |_| false),
+            "a{b:c}"
+        );
+        // the first selector is left intact
+        assert_eq!(minify_str("\u{feff}.a { color: red }"), ".a{color:red}");
+        assert_eq!(
+            minify_str(std::str::from_utf8(b"\xef\xbb\xbf\n.a{}").unwrap()),
+            ".a{}"
+        );
+        // only a leading byte order mark is removed
+        assert_eq!(
+            minify_with_predicate("a{content:\"\u{feff}\"}", |_| false),
+            "a{content:\"\u{feff}\"}"
+        );
+    }
+
+    #[test]
+    fn take_errors() {
+        let css = "\u{feff}a { : red; b: url(c";
+        let mut minifier = Minifier::new(MinifyOptions::default());
+        minifier.minify_string(css);
+        let errors: Vec<_> = minifier
+            .take_errors()
+            .iter()
+            .map(|e| (e.to_string(), e.offset()))
+            .collect();
+        assert_eq!(
+            errors,
+            [
+                ("declaration with empty property name".to_string(), 7),
+                ("reached end of input while inside url".to_string(), 17),
+            ]
+        );
+        assert!(minifier.take_errors().is_empty());
+        assert_eq!(minifier.get_output(), "a{:red;b:url(c");
+
+        let mut minifier = Minifier::new(MinifyOptions::default());
+        minifier.minify_string("a { content: 'b } /* c");
+        let errors = minifier.take_errors();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].offset(), 13);
+    }
+
+    #[test]
+    #[cfg(feature = "trace")]
+    fn trace() {
+        let css = "a{color:#ffffff}";
+        let mut minifier = Minifier::new(MinifyOptions::default());
+        minifier.minify_string(css);
+        assert_eq!(
+            minifier.trace_log(),
+            [
+                "0 'a' copy",
+                "1 '{' enter block",
+                "2 'c' copy",
+                "3 'o' copy",
+                "4 'l' copy",
+                "5 'o' copy",
+                "6 'r' copy",
+                "7 ':' colon",
+                "8 '#' hex color",
+                "15 '}' exit block",
+            ]
+        );
+    }
+
+    #[test]
+    fn size_stats() {
+        let mut minifier = Minifier::new(MinifyOptions::default());
+        minifier.minify_string("a { color: #ffffff; }");
+        let stats = minifier.size_stats();
+        assert_eq!(
+            stats,
+            MinifyStats {
+                input_len: 21,
+                output_len: 13,
+                bytes_saved: 8,
+                percent_saved: 8_f32 * 100_f32 / 21_f32,
+            }
+        );
+        assert_eq!(
+            stats.to_string(),
+            "minified 21 bytes to 13, saving 8 (38.1%)"
+        );
+        let mut minifier = Minifier::new(MinifyOptions::default());
+        minifier.minify_string("");
+        assert_eq!(minifier.size_stats(), MinifyStats::default());
+    }
+
+    #[test]
+    fn line_col() {
+        let css =
+            "a {\n  color: red;\n}\nb {\n  content: \"é ok\";\n  font-family: \"unclosed;\n}\n";
+        let mut minifier = Minifier::new(MinifyOptions::default());
+        minifier.minify_string(css);
+        let errors = minifier.take_errors();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].to_string(),
+            "reached end of input while inside quote string"
+        );
+        assert_eq!(minifier.line_col(errors[0].offset()), (6, 16));
+        // columns are counted in chars, not bytes
+        assert_eq!(minifier.line_col(css.find("ok").unwrap()), (5, 15));
+        assert_eq!(minifier.line_col(0), (1, 1));
+
+        let mut minifier = Minifier::new(MinifyOptions::default());
+        minifier.minify_string("\u{feff}a { : red }");
+        let errors = minifier.take_errors();
+        assert_eq!(minifier.line_col(errors[0].offset()), (1, 5));
+    }
+
+    #[test]
+    fn minify_reader() {
+        let css = "a { color: #ffffff; }\n".repeat(1000);
+        let mut minifier = Minifier::new(MinifyOptions::default());
+        minifier
+            .minify_reader(std::io::Cursor::new(css.clone().into_bytes()))
+            .unwrap();
+        assert_eq!(minifier.size_stats().input_len, css.len());
+        assert_eq!(minifier.get_output(), minify_str(&css));
+
+        // errors are located as for a str
+        let mut minifier = Minifier::new(MinifyOptions::default());
+        minifier
+            .minify_reader(std::io::Cursor::new(b"a {\n  b: 'c\n}".to_vec()))
+            .unwrap();
+        let errors = minifier.take_errors();
+        assert_eq!(minifier.line_col(errors[0].offset()), (2, 6));
+
+        let mut minifier = Minifier::new(MinifyOptions::default());
+        let error = minifier
+            .minify_reader(std::io::Cursor::new(vec![b'a', 0xff]))
+            .unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn reused_preserved_comment() {
+        // should not panic on the offsets of quotes from a previous input
+        let mut minifier = Minifier::new(MinifyOptions::default());
+        minifier.minify_string("a{b:\"xyz\"}");
+        minifier.minify_string("a{b:/x}");
+        assert_eq!(minifier.get_output(), "a{b:/x}");
+    }
+
+    #[test]
+    fn reused_minifier() {
+        let mut minifier = Minifier::new(MinifyOptions::default());
+        minifier.minify_string("/* a */ a { color: #ffffff; b: 'c }");
+        assert_eq!(minifier.stats().comments, 1);
+        minifier
+            .minify_reader(std::io::Cursor::new(b"a {\n  : red;\n}".to_vec()))
+            .unwrap();
+        // nothing is carried over from the first input
+        assert_eq!(minifier.stats().comments, 0);
+        assert_eq!(minifier.stats().semicolons, 1);
+        let errors = minifier.take_errors();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(minifier.line_col(errors[0].offset()), (2, 3));
+        assert_eq!(minifier.size_stats().input_len, 14);
+        minifier.minify_string("a { color: #ffffff; }");
+        assert_eq!(minifier.stats().colors, 1);
+        assert_eq!(minifier.get_output(), "a{color:#fff}");
+    }
+}
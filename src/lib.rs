@@ -17,13 +17,12 @@
 //!
 //! <div class="warning">
 //!
-//! ***IMPORTANT!*** the current version of `const_css_minify` resolves paths relative to the crate
-//! root (i.e. the directory where your `Cargo.toml` is). This behaviour is ***DIFFERENT*** from the
-//! rust built-in macros like `include_str!()` which use a path relative to the source file from
-//! which it's invoked. Consider the current behaviour unstable and likely to change  - our
-//! preference would be to match the established convention, but implementing this change is
-//! dependant on the stabilisation of a source path api in `proc_macro` as per
-//! <https://github.com/rust-lang/rust/issues/54725>
+//! ***IMPORTANT!*** `const_css_minify` resolves paths relative to the crate root (i.e. the
+//! directory where your `Cargo.toml` is), and only if there's no such file does it resolve them
+//! relative to the source file from which it's invoked, like the rust built-in macros such as
+//! `include_str!()`. The crate root takes precedence so that existing paths keep working, but
+//! consider this unstable and likely to change - our preference would be to match the
+//! established convention.
 //!
 //! </div>
 //!
@@ -37,9 +36,13 @@
 //!         margin: 10px 10px;
 //!     }
 //! "#);
-//! assert_eq!(CSS, "input[type=\"radio\"]:checked,.button:hover{color:#0ffc;margin:10px 10px}");
+//! assert_eq!(CSS, "input[type=radio]:checked,.button:hover{color:#0ffc;margin:10px 10px}");
 //! ```
 //!
+//! To save repeating the boilerplate for each stylesheet, the whole const item can be produced
+//! instead with `minify!(pub CSS => "./path/to/style.css");`, which also documents the const with
+//! its minified and original sizes.
+//!
 //! Note also that the current version of `const_css_minify` does not support passing in a variable.
 //! only the above two patterns of a path to an external file or a literal str will work.
 //!
@@ -48,15 +51,35 @@
 //!
 //! #### `const_css_minify` ***will:***
 //! * remove unneeded whitespace and linebreaks
-//! * remove comments
-//! * remove unneeded trailing semicolon in each declaration block
+//! * remove comments, except important comments beginning with `/*!`, such as licenses
+//! * remove a leading byte order mark, which is redundant in utf-8
+//! * remove unneeded semicolons in each declaration block, e.g. `a{;color:red;;}` becomes
+//!   `a{color:red}`
 //! * opportunistically minify colors specified either by literal hex values or by `rgb()`,
-//!   `rgba()`, `hsl()` and `hsla()` functions (in either legacy syntax with commas or modern
-//!   syntax without commas) without changing the color. e.g. `#ffffff` will be substituted with
-//!   `#fff`, `hsl(180 50 50)` with `#40bfbf`, `rgba(20%, 40%, 60%, 0.8)` with `#369c`, etc.
+//!   `rgba()`, `hsl()`, `hsla()` and `hwb()` functions (in either legacy syntax with commas or
+//!   modern syntax without commas) without changing the color. e.g. `#ffffff` will be substituted
+//!   with `#fff`, `hsl(180 50 50)` with `#40bfbf`, `rgba(20%, 40%, 60%, 0.8)` with `#369c`, etc.
 //!   `const-css-minify` will not attempt to calculate nested/complicated/relative rgb expressions
 //!   (which will be passed through unadulturated for the end user's browser to figure out for
 //!   itself) but many simple/literal expressions will be resolved and minified.
+//!   `oklab()` and `oklch()` functions are also minified to hex, unless the color lies outside
+//!   the range of colors which hex can represent.
+//!   Hex colors are written in lowercase, e.g. `#4CAF50` becomes `#4caf50`.
+//! * substitute a named color making up the whole value of a color property with its hex
+//!   color where that is shorter, e.g. `color:white` becomes `color:#fff`, and `transparent`
+//!   anywhere in a value which takes a color with `#0000`
+//! * remove the unit of a zero length, e.g. `margin:0px` becomes `margin:0`, except in functions
+//!   like `calc()`, custom properties and `flex`, where a unitless zero means something different
+//! * remove the leading zero of a fractional number, e.g. `opacity:0.5` becomes `opacity:.5`
+//! * normalize important annotations, e.g. `color: red ! IMPORTANT` becomes `color:red!important`
+//! * remove the quotes around a url where they aren't needed, e.g. `url("image.png")` becomes
+//!   `url(image.png)`. Data uris and urls with whitespace, parens or quotes keep their quotes.
+//! * remove the whitespace in attribute selectors, and the quotes around values which are
+//!   identifiers, e.g. `[ type = "text" ]` becomes `[type=text]`
+//! * remove the whitespace around `*` in `calc()` and other math functions, e.g.
+//!   `calc(2 * 1em + 1px)` becomes `calc(2*1em + 1px)`, keeping the required whitespace around `+`
+//!   and `-`
+//! * substitute `700` for `font-weight:bold` and `400` for `font-weight:normal`, which are equal
 //! * silently ignore css syntax errors originating in your source file*, and in so doing possibly
 //!   elicit slightly different failure modes from renderers by altering the placement of
 //!   whitespace around misplaced operators
@@ -64,661 +87,951 @@
 //! #### `const_css_minify` will ***not:***
 //! * compress your css using `gz`, `br` or `deflate`
 //! * change the semantic meaning of your semantically valid css
-//! * make any substitutions other than identical literal colors
+//! * make any substitutions other than identical literal colors and zero lengths
 //! * alert you to invalid css* - it's not truly parsing the css, just scanning for and removing
 //!   characters it identifies as unnecessary
 //!
 //! note*: The current version of `const-css-minify` will emit compile-time warning messages for
-//! some syntax errors (specifically unclosed quote strings and comments) which indicate an error
-//! in the css (or a bug in `const-css-minify`), however these messages do not offer much help to
-//! the user to locate the source of the error. Internally, these error states are identifed and
-//! handled to avoid panicking due to indexing out-of-bounds, and so reporting the error message at
-//! compile time is in a sense 'for free', but this is a non-core feature of the library and may be
-//! removed in a future version if it turns out to do more harm than good. In any case,
-//! `const-css-minify` generally assumes it is being fed valid css as input and offers no
-//! guarantees about warnings. `const-css-minify` should not be relied upon for linting of css.
+//! some syntax errors (specifically unclosed quote strings, comments and urls, and declarations
+//! missing a property name) which indicate an error in the css (or a bug in `const-css-minify`),
+//! however these messages do not offer much help to the user to locate the source of the error.
+//! Internally, these error states are identifed and handled to avoid panicking due to indexing
+//! out-of-bounds, and so reporting the error message at compile time is in a sense 'for free', but
+//! this is a non-core feature of the library and may be removed in a future version if it turns out
+//! to do more harm than good. In any case, `const-css-minify` generally assumes it is being fed
+//! valid css as input and offers no guarantees about warnings. `const-css-minify` should not be
+//! relied upon for linting of css. The warnings can be silenced with the `quiet` option below.
+//!
+//! #### concatenation
+//! The output of `minify!()` for a stylesheet in which every rule and statement is complete will
+//! itself end with a complete rule or statement (i.e. a closing `}` or a `;`), which means
+//! concatenating the output of several invocations at runtime is equivalent to minifying the
+//! concatenated source. This does not hold if a source is incomplete, for example if it ends with
+//! a bare selector or an unterminated `@import`, in which case it could run together with whatever
+//! follows it. Use `minify_fragment!()` in place of `minify!()` to have such a fragment rejected at
+//! compile time.
+//!
+//! Several files can be given to a single `minify!()`, separated by commas, in which case each is
+//! minified separately and the results are concatenated in order, e.g.
+//! `minify!("./reset.css", "./layout.css", "./theme.css")`. Compilation fails if any of them
+//! can't be found.
+//!
+//! Several files can also be given as a glob pattern relative to the crate root, preceded by
+//! `glob`, e.g. `minify!(glob "styles/*.css")`, in which `*` matches any run of characters and `?`
+//! any single character within a path component. The matching files are minified and concatenated
+//! in lexicographic order of their paths, and compilation fails if there are none.
+//!
+//! #### templates
+//! Placeholder tokens in css templates which are replaced by some later step, such as `__COLOR__`,
+//! are treated like any other identifier and survive minification intact. Placeholders delimited
+//! by braces, such as `{{primary}}`, are treated like declaration blocks, so they are also left
+//! intact where they make up a whole value, but whitespace inside them or between them and other
+//! values is removed, e.g. `margin: {{ gap }} 0` becomes `margin:{{gap}}0`.
+//!
+//! #### options
+//! Some transforms are more aggressive than the defaults above and must be opted in to by passing
+//! option identifiers ahead of the input, separated by commas:
+//!
+//! ```rust
+//! use const_css_minify::minify;
+//!
+//! const CSS: &str = minify!(collapse_zeros, "div { margin: 0 0 0 0 }");
+//! assert_eq!(CSS, "div{margin:0}");
+//! ```
+//!
+//! * `collapse_shorthands` - drop repeated values from a box shorthand (`margin`, `padding`,
+//!   `inset`, etc.) according to the rule for 1-4 values, e.g. `margin:1px 2px 1px 2px` becomes
+//!   `margin:1px 2px`. Values containing functions such as `var()` are left as they are.
+//! * `collapse_zeros` - collapse a box shorthand (`margin`, `padding`, `inset`, etc.) whose values
+//!   are all zero into a single `0`, e.g. `padding:0px 0%` becomes `padding:0`
+//! * `dedupe_declarations` - remove a declaration which is identical to the one before it in the
+//!   same block once minified, e.g. `color:red;color:red` becomes `color:red`. Duplicates which
+//!   aren't adjacent are left as they are, as removing one could change which declaration wins.
+//! * `inline_imports` - replace each unconditional `@import` of a relative path with the minified
+//!   content of the imported file, rewriting relative `url()` references in the imported file so
+//!   they remain correct relative to the top-level file. Imports with `layer`, `supports` or media
+//!   conditions can't be inlined, and if there are any then all imports are left as they are.
+//! * `lossless_alpha` - only convert a color function with an alpha channel to hex if the alpha
+//!   maps exactly to an 8 bit hex alpha, e.g. `rgba(0,0,0,0.2)` becomes `#0003` but
+//!   `rgba(0,0,0,0.5)` is left as it is because `#00000080` has an alpha of `0.50196..`
+//! * `max_color_error` - only convert a color function to hex if its red, green and blue channels
+//!   map exactly to bytes, e.g. `rgb(20%,40%,60%)` becomes `#369` but `rgb(33%,0%,0%)` is left as
+//!   it is because 33% of 255 is `84.15`. A tolerance out of 255 may be given instead, e.g.
+//!   `max_color_error = 0.2`. The alpha channel is governed by `lossless_alpha`.
+//! * `no_color` - leave colors exactly as they are written, e.g. `#ffffff` and `rgb(0,0,0)`
+//!   survive unchanged, while still removing whitespace and comments and so on
+//! * `quiet` - don't emit compile-time warning messages for syntax errors, which is useful if you
+//!   are intentionally feeding `const_css_minify` imperfect css
+//! * `shortest_colors` - write each color which is converted or minified in a value in its shortest
+//!   form, including named colors and dropping a fully opaque alpha channel, e.g. `rgb(255,0,0)`
+//!   and `#ff0000ff` become `red`. Colors in custom properties are left as hex.
+//! * `strip_empty_declarations` - remove declarations with an empty value, e.g. `color:;`, which
+//!   are invalid and have no effect. Custom properties are left as they are, because `--x:;`
+//!   validly sets `--x` to an empty value.
+//! * `strip_empty_rules` - remove rules whose block is empty once minified, e.g.
+//!   `a{}b{color:red}` becomes `b{color:red}`, including `@media`, `@supports` and `@container`
+//!   rules which are left empty. Other at-rules such as `@layer` are left as they are, because
+//!   they can have an effect even when empty.
+//! * `round_decimals` - round decimal numbers in values with more than 4 fractional digits, e.g.
+//!   `width:33.33333333%` becomes `width:33.3333%`. This slightly changes the values, which is very
+//!   unlikely to be noticeable. The number of fractional digits may be given instead of the default
+//!   4, e.g. `round_decimals = 2`. Numbers in custom properties, quotes and urls are not rounded.
+//! * `verbatim = "prefix"` - copy the values of properties whose names start with `prefix` as they
+//!   are, apart from collapsing whitespace and removing comments. This is an escape hatch for
+//!   properties with unusual syntax which `const_css_minify` might mishandle, and may be given more
+//!   than once. The legacy `-webkit-gradient()` function is always copied as it is.
+//!
+//! Setting the environment variable `CONST_CSS_MINIFY_STATS=1` while compiling prints a line to
+//! stderr for each file minified, giving its size before and after minification and the bytes
+//! saved.
+//!
+//! If you suspect `const_css_minify` of mangling your css, enabling the `trace` feature will log
+//! each decision it takes while scanning your css to stderr at compile time, which is very helpful
+//! to include in a bug report.
 //!
 //! `const_css_minify` is a lightweight solution - the current version of `const_css_minify` has
-//! zero dependencies outside rust's built-in std and proc_macro libraries.
+//! zero dependencies outside rust's built-in std and proc_macro libraries, other than its own
+//! [`const-css-minify-core`](https://docs.rs/const-css-minify-core) crate, which exposes the
+//! minifier for use at runtime or in build scripts.
 
-use proc_macro::TokenStream;
-use proc_macro::TokenTree::Literal;
-use std::collections::HashMap;
-use std::fmt;
+use const_css_minify_core::{is_ident_byte, Minifier, MinifyOptions};
+use proc_macro::TokenTree::{Ident, Literal, Punct};
+use proc_macro::{Span, TokenStream};
 use std::fs;
-use std::path::Path;
+use std::io::ErrorKind;
+use std::path::{Component, Path, PathBuf};
 use std::str::FromStr;
 
 /// Produce a minified css file as an inline const
+///
+/// The input may instead be preceded by the name of a const, optionally preceded by a visibility,
+/// and `=>`, in which case a whole const item is produced. The generated const is documented with
+/// a line like `minified: 1234 bytes (was 5678)`, which shows up in rustdoc and IDE hovers.
+///
+/// ```rust
+/// use const_css_minify::minify;
+///
+/// minify!(pub CSS => "a { color: #ffffff; }");
+/// assert_eq!(CSS, "a{color:#fff}");
+/// ```
 #[proc_macro]
 pub fn minify(input: TokenStream) -> TokenStream {
     let token_trees: Vec<_> = input.into_iter().collect();
-    if token_trees.len() != 1 {
-        panic!("const_css_minify requires a single str as input");
-    }
-    let Literal(literal) = token_trees.first().unwrap() else {
-        panic!("const_css_minify requires a literal str as input");
-    };
-    let mut literal = literal.to_string();
-
-    // not a raw string, so we must de-escape special chars
-    // this is not comprehensive but is anyone ever going to even notice?
-    // what weird and strange things might they even be trying to achieve?
-    if let Some(c) = literal.get(0..=0) {
-        if c != "r" {
-            literal = literal
-                .replace("\\\"", "\"")
-                .replace("\\n", "\n")
-                .replace("\\r", "\r")
-                .replace("\\t", "\t")
-                .replace("\\\\", "\\")
-        }
-    }
-
-    // trim leading and trailing ".." or r#".."# from string literal
-    let start = &literal.find('\"').unwrap() + 1;
-    let end = &literal.rfind('\"').unwrap() - 1;
-    //bail if literal is empty
-    if start > end {
-        return TokenStream::from_str(&literal).unwrap();
-    }
-    literal = literal[start..=end].to_string();
+    let arrow = token_trees.windows(2).position(
+        |w| matches!(w, [Punct(eq), Punct(gt)] if eq.as_char() == '=' && gt.as_char() == '>'),
+    );
+    let Some(arrow) = arrow else {
+        let minified = minify_input(token_trees.into_iter().collect());
 
-    // check if we're dealing with path or literal
-    let mut minified = fs::read_to_string(Path::new(&literal)).unwrap_or(literal);
-
-    let mut minifier = Minifier::new();
-    minifier.minify_string(&minified);
-    minifier.emit_error_msgs();
-    minified = minifier.get_output();
-
-    // wrap in quotes, ready to emit as rust raw str token
-    minified = "r####\"".to_string() + &minified + "\"####";
-
-    TokenStream::from_str(&minified).unwrap()
+        // wrap in quotes, ready to emit as rust raw str token
+        return TokenStream::from_str(&raw_str(&minified)).unwrap();
+    };
+    let mut visibility = token_trees[..arrow].to_vec();
+    let Some(Ident(name)) = visibility.pop() else {
+        panic!("const_css_minify requires the name of the const followed by => as input");
+    };
+    let visibility: TokenStream = visibility.into_iter().collect();
+    let (sources, options) = parse_input(token_trees[arrow + 2..].iter().cloned().collect());
+    let minified = minify_sources(&sources, &options);
+    let len: usize = sources.iter().map(|(css, _)| css.len()).sum();
+    let doc = format!("minified: {} bytes (was {})", minified.len(), len);
+    TokenStream::from_str(&format!(
+        "#[doc = {:?}] {} const {}: &str = {};",
+        doc,
+        visibility,
+        name,
+        raw_str(&minified)
+    ))
+    .unwrap()
 }
 
-struct ParseError {
-    msg: String,
+/// Produce a minified css file as an inline byte string const
+///
+/// This accepts the same input as `minify!()`, but emits a byte string literal, i.e. a
+/// `&[u8; N]` with a known length, which is handy for serving the css directly as bytes:
+///
+/// ```rust
+/// use const_css_minify::minify_bytes;
+///
+/// const CSS: &[u8; 13] = minify_bytes!("a { color: #ffffff; }");
+/// assert_eq!(CSS, b"a{color:#fff}");
+/// ```
+#[proc_macro]
+pub fn minify_bytes(input: TokenStream) -> TokenStream {
+    let minified = minify_input(input);
+    TokenStream::from_str(&byte_str(&minified)).unwrap()
 }
 
-impl ParseError {
-    pub fn from_msg(msg: &str) -> Self {
-        Self {
-            msg: msg.to_string(),
-        }
+/// Produce a minified css fragment as an inline const, guaranteed to be safe to concatenate
+///
+/// This is identical to `minify!()` except that compilation fails if the minified output doesn't
+/// end at a safe boundary, i.e. it has an unclosed block, string or paren, or ends in the middle
+/// of a selector or statement:
+///
+/// ```rust
+/// use const_css_minify::minify_fragment;
+///
+/// const CSS: &str = minify_fragment!("@import 'base.css'; .a { color: red }");
+/// ```
+/// ```compile_fail
+/// use const_css_minify::minify_fragment;
+///
+/// const CSS: &str = minify_fragment!(".a { color: red } .b");
+/// ```
+#[proc_macro]
+pub fn minify_fragment(input: TokenStream) -> TokenStream {
+    let minified = minify_input(input);
+    if !is_concatenation_safe(&minified) {
+        let msg = format!(
+            "const_css_minify fragment does not end at a safe boundary: {}",
+            minified
+        );
+        return TokenStream::from_str(&format!("compile_error!({:?})", msg)).unwrap();
     }
+    TokenStream::from_str(&raw_str(&minified)).unwrap()
 }
 
-impl fmt::Display for ParseError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.msg)
+/// Produce a minified css file as an inline const, failing compilation on any syntax error
+///
+/// This accepts the same input as `minify!()`, but where `minify!()` tolerates syntax errors and
+/// only warns about them, this fails compilation with a message listing each of them, which makes
+/// it a light sanity check on your css:
+///
+/// ```rust
+/// use const_css_minify::minify_strict;
+///
+/// const CSS: &str = minify_strict!("a { color: #ffffff; }");
+/// assert_eq!(CSS, "a{color:#fff}");
+/// ```
+/// ```compile_fail
+/// use const_css_minify::minify_strict;
+///
+/// const CSS: &str = minify_strict!("a { color: #ffffff; } /* unclosed");
+/// ```
+#[proc_macro]
+pub fn minify_strict(input: TokenStream) -> TokenStream {
+    let (sources, options) = parse_input(input);
+    let errors: Vec<_> = sources
+        .iter()
+        .flat_map(|(css, _)| {
+            let mut minifier = Minifier::new(options.clone());
+            minifier.minify_string(css);
+            minifier
+                .take_errors()
+                .into_iter()
+                .map(|e| {
+                    let (line, col) = minifier.line_col(e.offset());
+                    format!("{} at line {}, col {}", e, line, col)
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    if !errors.is_empty() {
+        let msg = format!("const_css_minify parse error: {}", errors.join("; "));
+        return TokenStream::from_str(&format!("compile_error!({:?})", msg)).unwrap();
     }
+    let minified = minify_sources(&sources, &options);
+    TokenStream::from_str(&raw_str(&minified)).unwrap()
 }
 
-// we do not attempt to decode all valid rgb func expressions, but we do attempt simple expressions
-// that consist of purely literal numeric expressions.
-const RGB_FUNC_DECODABLE: [u8; 15] = [
-    b'0', b'1', b'2', b'3', b'4', b'5', b'6', b'7', b'8', b'9', b' ', b',', b'%', b'.', b'/',
-];
+/// Produce the original, unminified css file as an inline const
+///
+/// This accepts the same input as `minify!()` and reads files in the same way, but skips
+/// minification entirely, so swapping one macro for the other is an easy way to check whether a
+/// rendering problem is caused by minification:
+///
+/// ```rust
+/// use const_css_minify::minify_passthrough;
+///
+/// const CSS: &str = minify_passthrough!(collapse_zeros, "a { margin: 0 0; }");
+/// assert_eq!(CSS, "a { margin: 0 0; }");
+/// ```
+#[proc_macro]
+pub fn minify_passthrough(input: TokenStream) -> TokenStream {
+    let (sources, _) = parse_input(input);
+    let css: String = sources.into_iter().map(|(css, _)| css).collect();
+    TokenStream::from_str(&raw_str(&css)).unwrap()
+}
 
-/*
- * css is relatively simple but there are a few gotchas. Nested classes basically means any
- * property can be a selector, so we can't generically distinguish between the two without
- * a lookup to known legal names, and also the fact that pseudo classes and elements are
- * denoted with ':' which is also the value assignment operator means we need to scan ahead to
- * decide if a particular ':' on the input is part of a selector and requires leading
- * whitespace to be preserved, or if it's the assignment operator and doesn't require leading
- * whitespace. To avoid re-implementing comment and quote handling while scanning forward, we
- * instead mark the index as a backreference and remove it later if we can. This also has the
- * conseqence that we also cannot generically identify if we are currently parsing a property
- * or a value without a lookup to known legal names, which as far as I know shouldn't cause
- * problems for handling correct css but eliminates some avenues for error tolerance. But
- * intelligent handling of incorrect css is beyond this scope of this crate so this is
- * acceptable.
- */
-struct Minifier<'a> {
-    input: Option<&'a [u8]>,
-    output0: Vec<u8>,
-    output1: Vec<u8>,
-    // start and end indexes
-    quotes0: HashMap<usize, usize>,
-    errors: Vec<ParseError>,
+/// Produce a minified css file as an inline const, escaped for use inside a javascript template
+/// literal
+///
+/// This accepts the same input as `minify!()`, but escapes backslashes, backticks and `${` in the
+/// output, so it can be written between backticks when generating javascript:
+///
+/// ```rust
+/// use const_css_minify::minify_js_template;
+///
+/// const CSS: &str = minify_js_template!(r#"a::before { content: "`${x}`"; }"#);
+/// assert_eq!(CSS, r#"a::before{content:"\`\${x}\`"}"#);
+/// let js = format!("const css = `{}`;", CSS);
+/// ```
+#[proc_macro]
+pub fn minify_js_template(input: TokenStream) -> TokenStream {
+    let minified = minify_input(input)
+        .replace('\\', "\\\\")
+        .replace('`', "\\`")
+        .replace("${", "\\${");
+    TokenStream::from_str(&raw_str(&minified)).unwrap()
 }
 
-impl<'a> Minifier<'a> {
-    pub fn get_output(self) -> String {
-        String::from_utf8(self.output1).unwrap()
+/// Produce a minified css file as an inline const, and also write it to a file in `OUT_DIR`
+///
+/// The last argument is the name of the file to write, relative to `OUT_DIR`, and the rest are as
+/// for `minify!()`. This is useful if you also want to serve the minified css as a static file.
+/// `OUT_DIR` is only set when compiling a crate which has a build script, and compilation fails
+/// if it isn't set, as it also does if the file would be outside `OUT_DIR`. So in a crate with a
+/// build script:
+///
+/// ```rust,ignore
+/// use const_css_minify::minify_to_file;
+///
+/// const CSS: &str = minify_to_file!("a { color: #ffffff; }", "style.min.css");
+/// assert_eq!(CSS, "a{color:#fff}");
+/// ```
+#[proc_macro]
+pub fn minify_to_file(input: TokenStream) -> TokenStream {
+    const USAGE: &str =
+        "const_css_minify requires the css followed by a comma and the name of the output file";
+    let mut token_trees: Vec<_> = input.into_iter().collect();
+    let (Some(Literal(file_name)), Some(Punct(comma))) = (token_trees.pop(), token_trees.pop())
+    else {
+        panic!("{}", USAGE);
+    };
+    if comma.as_char() != ',' {
+        panic!("{}", USAGE);
     }
-
-    pub fn new() -> Self {
-        Self {
-            input: None,
-            output0: Vec::<u8>::with_capacity(0),
-            output1: Vec::<u8>::with_capacity(0),
-            quotes0: HashMap::<usize, usize>::new(),
-            errors: Vec::<ParseError>::new(),
-        }
+    let file_name = literal_content(&file_name);
+    // an absolute path would replace OUT_DIR when joined, and `..` could climb out of it
+    if !Path::new(&file_name)
+        .components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        panic!(
+            "const_css_minify can only write to a file within OUT_DIR: {}",
+            file_name
+        );
     }
-
-    pub fn minify_string(&mut self, input: &'a String) {
-        self.input = Some(input.as_bytes());
-        self.pass0();
-        self.pass1();
+    let minified = minify_input(token_trees.into_iter().collect());
+    let Ok(out_dir) = std::env::var("OUT_DIR") else {
+        panic!("const_css_minify can only write to a file when OUT_DIR is set by a build script");
+    };
+    let path = Path::new(&out_dir).join(file_name);
+    if let Err(e) = fs::write(&path, &minified) {
+        panic!("const_css_minify could not write {}: {}", path.display(), e);
     }
+    TokenStream::from_str(&raw_str(&minified)).unwrap()
+}
 
-    fn add_error_msg(&mut self, msg: &str) {
-        self.errors.push(ParseError::from_msg(msg));
-    }
+// parse macro input consisting of options followed by a literal, and minify it
+fn minify_input(input: TokenStream) -> String {
+    let (sources, options) = parse_input(input);
+    minify_sources(&sources, &options)
+}
+
+// minify each source separately, so that relative references in each are resolved against its own
+// directory, and concatenate the results
+fn minify_sources(sources: &[(String, PathBuf)], options: &MinifyOptions) -> String {
+    sources
+        .iter()
+        .map(|(css, dir)| minify_loaded(css, dir, options))
+        .collect()
+}
 
-    fn emit_error_msgs(&self) {
-        for error in &self.errors {
-            eprintln!("WARN! const-css-minify parse error: {}", error);
+// minify css loaded from a file in dir (or a literal, for which dir is the crate root)
+fn minify_loaded(css: &str, dir: &Path, options: &MinifyOptions) -> String {
+    let mut minified = minify_css(css, options);
+    if options.inline_imports {
+        match inline_imports(&minified, dir, Path::new(""), options, 0) {
+            Some(inlined) => minified = inlined,
+            None if options.quiet => (),
+            None => eprintln!(
+                "WARN! const-css-minify could not inline conditional or non-relative @import, \
+                leaving all imports in place"
+            ),
         }
     }
+    minified
+}
 
-    //collapse all whitespace sequences into single ' ', remove comments,
-    //mark quotes in output stream
-    fn pass0(&mut self) {
-        let input = self.input.unwrap();
-        let len = input.len();
-        let mut output = Vec::<u8>::with_capacity(len);
-        let mut read = 0;
-        loop {
-            match read {
-                i if i == len => break,
-                i if i > len => unreachable!(), // to catch errors of reasoning in indexing
-                _ => (),
+// parse macro input consisting of options followed by one or more literals, returning the css of
+// each, with the directory against which its relative references are resolved, and the options
+fn parse_input(input: TokenStream) -> (Vec<(String, PathBuf)>, MinifyOptions) {
+    // split input on commas, leading segments are options and the rest are css
+    let mut segments = vec![Vec::new()];
+    for token_tree in input {
+        match token_tree {
+            Punct(p) if p.as_char() == ',' => segments.push(Vec::new()),
+            _ => segments.last_mut().unwrap().push(token_tree),
+        }
+    }
+    let first_literal = segments
+        .iter()
+        .position(|s| match s.as_slice() {
+            [Literal(_)] => true,
+            [Ident(ident), Literal(_)] => ident.to_string() == "glob",
+            _ => false,
+        })
+        .unwrap_or(segments.len());
+    let literals = segments.split_off(first_literal);
+    let mut options = MinifyOptions::default();
+    for segment in segments {
+        match segment.as_slice() {
+            [Ident(ident)] => options.set(&ident.to_string()),
+            [Ident(ident), Punct(eq), Literal(value)] if eq.as_char() == '=' => {
+                // a numeric literal has no quotes to trim
+                let value = match value.to_string() {
+                    v if v.contains('"') => literal_content(value),
+                    v => v,
+                };
+                options.set_value(&ident.to_string(), &value)
             }
-            match input[read] {
-                // trim excess whitespace, convert to space
-                w if w.is_ascii_whitespace() => {
-                    // if the last element was a comment that was entirely ignored, and if the
-                    // comment was preceeded by whitespace, we might end up with two consecutive
-                    // whitespaces, which violates the promise of this method. Thus we explicitly
-                    // check and remove it if present.
-                    if let Some(last) = output.pop() {
-                        if last != b' ' {
-                            output.push(last);
-                        }
-                    }
-                    read += 1;
-                    while read < len && input[read].is_ascii_whitespace() {
-                        read += 1;
-                    }
-                    // don't add whitespace to head or tail
-                    if !output.is_empty() && read < len {
-                        output.push(b' ');
-                    }
-                }
-                // css comments
-                b'/' if len > read + 1 && input[read + 1] == b'*' => {
-                    let mut found_end = false;
-                    // move read index to first char after '*' in the matched pattern, or possibly
-                    // past the end of input if '/*' are the last two chars.
-                    read += 2;
-                    // below we are comparing against a '*' at read - 1, and we explicitly want to
-                    // avoid opening and closing a comment on '/*/' - a correct comment consists of
-                    // '/**/ at a minimum. Therefore we must increment read once more, but we only
-                    // want to do this if we aren't already beyond the end of input
-                    if read < len {
-                        read += 1;
-                    }
-                    while read < len {
-                        let s = &input[read - 1..=read];
-                        read += 1;
-                        if s == [b'*', b'/'] {
-                            found_end = true;
-                            break;
-                        }
-                    }
-                    if !found_end {
-                        self.add_error_msg("reached end of input while inside comment");
-                    }
+            _ => panic!(
+                "const_css_minify options must be identifiers, or identifiers assigned a literal \
+                str, separated by commas"
+            ),
+        }
+    }
+    let mut sources = Vec::new();
+    for segment in &literals {
+        if let [Ident(ident), Literal(pattern)] = segment.as_slice() {
+            if ident.to_string() == "glob" {
+                let pattern = literal_content(pattern);
+                let paths = expand_glob(&pattern);
+                if paths.is_empty() {
+                    panic!("const_css_minify glob matched no files: {}", pattern);
                 }
-                // quotes
-                q @ (b'"' | b'\'') => {
-                    let start = output.len();
-                    output.push(input[read]);
-                    read += 1;
-                    let mut found_end = false;
-                    while read < len {
-                        let b = input[read];
-                        output.push(b);
-                        read += 1;
-                        if b == q {
-                            found_end = true;
-                            break;
-                        }
-                    }
-                    if !found_end {
-                        self.add_error_msg("reached end of input while inside quote string");
+                for path in paths {
+                    if let (css, Some(dir)) = load_css(path.to_string_lossy().into_owned()) {
+                        sources.push((css, dir));
                     }
-                    let end = output.len() - 1;
-                    self.quotes0.insert(start, end);
-                }
-                _ => {
-                    output.push(input[read]);
-                    read += 1;
                 }
+                continue;
             }
         }
-        self.output0 = output;
-    }
-
-    fn pass1(&mut self) {
-        let input = &self.output0;
-        let len = input.len();
-        let mut output = Vec::<u8>::with_capacity(len);
-        let mut read = 0;
-        let mut peek;
-        let mut backreference = None;
-        loop {
-            match read {
-                i if i == len => break,
-                i if i > len => unreachable!(), // to catch errors of reasoning in indexing
-                _ => (),
-            }
-            match input[read] {
-                // copy quotes verbatim
-                b'\'' | b'"' => {
-                    let end = self.quotes0.get(&read).unwrap();
-                    while read <= *end {
-                        output.push(input[read]);
-                        read += 1
-                    }
-                }
-                // enter declaration block
-                b'{' => {
-                    backreference = None;
-                    if let Some(last) = output.pop() {
-                        if last != b' ' {
-                            output.push(last);
-                        }
-                    }
-                    output.push(input[read]);
-                    read += 1;
-                    // drop trailing space
-                    if read < len && input[read] == b' ' {
-                        read += 1;
-                    }
-                }
-                // exit declaration block
-                b'}' => {
-                    if let Some(br) = backreference {
-                        output.remove(br);
-                    }
-                    backreference = None;
-                    if let Some(last) = output.pop() {
-                        if last != b' ' {
-                            output.push(last);
-                        }
-                    }
-                    // drop final semicolon in declaration block
-                    if let Some(last) = output.pop() {
-                        if last != b';' {
-                            output.push(last);
-                        }
-                    }
-                    output.push(input[read]);
-                    read += 1;
-                    // drop trailing space
-                    if read < len && input[read] == b' ' {
-                        read += 1;
-                    }
-                }
-                // value assignement OR pseudo class/element
-                b':' => {
-                    backreference = None;
-                    // pseudo element
-                    if len > read + 1 && input[read + 1] == b':' {
-                        output.push(b':');
-                        output.push(b':');
-                        read += 2;
-                    } else {
-                        if let Some(last) = output.pop() {
-                            // mark backreference for possible future removal
-                            if last == b' ' {
-                                backreference = Some(output.len());
-                            }
-                            output.push(last);
-                        }
-                        output.push(input[read]);
-                        read += 1;
-                        // drop trailing space
-                        if read < len && input[read] == b' ' {
-                            read += 1;
-                        }
-                    }
-                }
-                // comma separator
-                b',' => {
-                    // drop spaces preceeding commas
-                    if let Some(last) = output.pop() {
-                        if last != b' ' {
-                            output.push(last);
-                        }
-                    }
-                    output.push(input[read]);
-                    read += 1;
-                    // drop trailing space
-                    if read < len && input[read] == b' ' {
-                        read += 1;
-                    }
-                }
-                // semicolon separator
-                b';' => {
-                    if let Some(br) = backreference {
-                        output.remove(br);
-                    }
-                    backreference = None;
-                    // drop leading space
-                    if let Some(last) = output.pop() {
-                        if last != b' ' {
-                            output.push(last);
-                        }
-                    }
-                    output.push(input[read]);
-                    read += 1;
-                    // drop trailing space
-                    if read < len && input[read] == b' ' {
-                        read += 1;
-                    }
-                }
+        let [Literal(literal)] = segment.as_slice() else {
+            panic!(
+                "const_css_minify requires a literal str as input, or several separated by commas"
+            );
+        };
+        let literal = literal_content(literal);
+        match load_css(literal.clone()) {
+            (css, Some(dir)) => sources.push((css, dir)),
+            // several literals must all be files, as css couldn't sensibly be split across literals
+            _ if literals.len() > 1 => panic!("const_css_minify could not find file: {}", literal),
+            (css, None) => sources.push((css, PathBuf::new())),
+        }
+    }
+    if sources.is_empty() {
+        panic!("const_css_minify requires a literal str as input");
+    }
+    (sources, options)
+}
 
-                // possible hex color
-                b'#' if len > read + 3 => {
-                    peek = read + 1;
-                    while len > peek && input[peek].is_ascii_hexdigit() {
-                        peek += 1;
-                    }
-                    if let Ok(mut hex_color) = try_minify_hex_color(&input[read..peek]) {
-                        output.append(&mut hex_color);
-                        read = peek;
-                    } else {
-                        output.push(input[read]);
-                        read += 1;
-                    }
-                }
-                // possible hsl func
-                b'h' if len > read + 9
-                    && (input[read + 1..=read + 3] == [b's', b'l', b'(']
-                        || input[read + 1..=read + 4] == [b's', b'l', b'a', b'(']) =>
-                {
-                    peek = read + 4;
-                    if input[peek] == b'(' {
-                        peek += 1;
-                    }
-                    while len > peek
-                        && input[peek] != b')'
-                        && RGB_FUNC_DECODABLE.contains(&input[peek])
-                    {
-                        peek += 1
-                    }
-                    if input[peek] == b')' {
-                        if let Ok(mut hex_color) = try_decode_hsl_func(&input[read..=peek]) {
-                            hex_color = try_minify_hex_color(&hex_color).unwrap();
-                            output.append(&mut hex_color);
-                            read = peek + 1;
-                            continue;
-                        }
-                    }
-                    output.push(input[read]);
-                    read += 1;
-                }
-                // possible rgb func
-                b'r' if len > read + 9
-                    && (input[read + 1..=read + 3] == [b'g', b'b', b'(']
-                        || input[read + 1..=read + 4] == [b'g', b'b', b'a', b'(']) =>
-                {
-                    peek = read + 4;
-                    if input[peek] == b'(' {
-                        peek += 1;
-                    }
-                    while len > peek
-                        && input[peek] != b')'
-                        && RGB_FUNC_DECODABLE.contains(&input[peek])
-                    {
-                        peek += 1
-                    }
-                    if input[peek] == b')' {
-                        if let Ok(mut hex_color) = try_decode_rgb_func(&input[read..=peek]) {
-                            hex_color = try_minify_hex_color(&hex_color).unwrap();
-                            output.append(&mut hex_color);
-                            read = peek + 1;
-                            continue;
-                        }
-                    }
-                    output.push(input[read]);
-                    read += 1;
-                }
-                // all else copy verbatim
-                _ => {
-                    output.push(input[read]);
-                    read += 1;
-                }
-            }
+/// Produce a single minified css file as an inline const from several css files, each wrapped in
+/// an `@media` block for the given media query
+///
+/// Unlike `minify!()`, a literal str isn't accepted in place of a file, so a missing file fails
+/// compilation rather than being minified as css.
+///
+/// ```rust
+/// use const_css_minify::minify_media;
+///
+/// const CSS: &str = minify_media! {
+///     "screen and (min-width: 800px)" => "./tests/media/screen.css",
+///     "print" => "./tests/media/print.css",
+/// };
+/// assert!(CSS.starts_with("@media screen and (min-width:800px){.nav{display:flex;"));
+/// ```
+#[proc_macro]
+pub fn minify_media(input: TokenStream) -> TokenStream {
+    const USAGE: &str =
+        "const_css_minify requires comma separated `\"query\" => \"css\"` pairs as input";
+    let token_trees: Vec<_> = input.into_iter().collect();
+    let mut css = String::new();
+    for pair in token_trees.split(|t| matches!(t, Punct(p) if p.as_char() == ',')) {
+        // permit a trailing comma
+        if pair.is_empty() {
+            continue;
+        }
+        let [Literal(query), Punct(eq), Punct(gt), Literal(source)] = pair else {
+            panic!("{}", USAGE);
+        };
+        if eq.as_char() != '=' || gt.as_char() != '>' {
+            panic!("{}", USAGE);
         }
-        self.output0.clear();
-        self.output0.shrink_to_fit();
-        self.output1 = output;
+        let query = literal_content(query);
+        let path = literal_content(source);
+        let (source, Some(_)) = load_css(path.clone()) else {
+            panic!("const_css_minify could not find file: {}", path);
+        };
+        css.push_str(&format!("@media {}{{{}}}", query, source));
     }
+    let minified = minify_css(&css, &MinifyOptions::default());
+    TokenStream::from_str(&raw_str(&minified)).unwrap()
 }
 
-/*
- * requires input to start with "hsl(" or "hsla(" and end with ")"
- */
-fn try_decode_hsl_func(input: &[u8]) -> Result<Vec<u8>, ()> {
-    let mut v = vec![b'#'];
-    let mut read = 3;
-    if input[read] == b'a' {
-        read += 1;
+/// Assert at compile time that two css files (or literal strs) minify to identical output
+///
+/// Expands to nothing if the minified outputs are equal, and to a `compile_error!` showing where
+/// they first differ otherwise. This is useful to check that reformatting or reorganising a
+/// stylesheet hasn't changed its effective content:
+///
+/// ```rust
+/// use const_css_minify::minify_assert_eq;
+///
+/// minify_assert_eq!("a { color: #ffffff; }", "a{color:#fff}");
+/// ```
+/// ```compile_fail
+/// use const_css_minify::minify_assert_eq;
+///
+/// minify_assert_eq!("a { color: #ffffff; }", "a{color:#000}");
+/// ```
+#[proc_macro]
+pub fn minify_assert_eq(input: TokenStream) -> TokenStream {
+    let token_trees: Vec<_> = input.into_iter().collect();
+    let [Literal(left), Punct(comma), Literal(right)] = token_trees.as_slice() else {
+        panic!("const_css_minify requires two literal strs separated by a comma as input");
+    };
+    if comma.as_char() != ',' {
+        panic!("const_css_minify requires two literal strs separated by a comma as input");
     }
-    if input[read] != b'(' {
-        return Err(());
+    let options = MinifyOptions::default();
+    let left = minify_css(&load_css(literal_content(left)).0, &options);
+    let right = minify_css(&load_css(literal_content(right)).0, &options);
+    if left == right {
+        return TokenStream::new();
     }
-    read += 1;
-    let mut hsla_d = [
-        String::with_capacity(10),
-        String::with_capacity(10),
-        String::with_capacity(10),
-        String::with_capacity(10),
-    ];
-    let mut percents = [false, false, false, false];
-    let mut i = 0;
 
-    while input[read] != b')' {
-        match input[read] {
-            x if !RGB_FUNC_DECODABLE.contains(&x) => return Err(()),
-            d if d.is_ascii_digit() || d == b'.' => hsla_d[i].push(char::from(d)),
-            b'%' => percents[i] = true,
-            b' ' | b',' | b'/' => {
-                i += 1;
-                while [b' ', b',', b'/'].contains(&input[read + 1]) {
-                    read += 1;
-                }
+    // show some context either side of the first difference
+    const CONTEXT: usize = 30;
+    let mut diff = left
+        .bytes()
+        .zip(right.bytes())
+        .position(|(l, r)| l != r)
+        .unwrap_or(left.len().min(right.len()));
+    while !left.is_char_boundary(diff) || !right.is_char_boundary(diff) {
+        diff -= 1;
+    }
+    let excerpt = |s: &str| {
+        let mut start = diff.saturating_sub(CONTEXT);
+        while !s.is_char_boundary(start) {
+            start -= 1;
+        }
+        let mut end = (diff + CONTEXT).min(s.len());
+        while !s.is_char_boundary(end) {
+            end += 1;
+        }
+        s[start..end].to_string()
+    };
+    let msg = format!(
+        "const_css_minify minified css differs at byte {}\n  left: {}\n right: {}",
+        diff,
+        excerpt(&left),
+        excerpt(&right)
+    );
+    TokenStream::from_str(&format!("compile_error!({:?});", msg)).unwrap()
+}
+
+// true if css is empty or ends with a complete rule or statement at the top level, in which case
+// it can be concatenated with other such css without the two running together. An escaped byte is
+// never a quote, brace or terminator.
+fn is_concatenation_safe(css: &str) -> bool {
+    let input = css.as_bytes();
+    let mut depth = 0_isize;
+    let mut quote = None;
+    let mut terminated = true;
+    let mut read = 0;
+    while read < input.len() {
+        terminated = false;
+        match (quote, input[read]) {
+            (_, b'\\') => read += 1,
+            (Some(q), b) if b == q => quote = None,
+            (Some(_), _) => (),
+            (None, q @ (b'"' | b'\'')) => quote = Some(q),
+            (None, b'{' | b'(') => depth += 1,
+            (None, b'}' | b')') => {
+                depth -= 1;
+                terminated = input[read] == b'}';
             }
-            _ => unreachable!(), // did we add chars to RGB_FUNC_DECODABLE and not match here?
+            (None, b';') => terminated = true,
+            _ => (),
         }
         read += 1;
     }
+    depth == 0 && quote.is_none() && terminated
+}
 
-    // check we got required input for h, s, l
-    for digits in &hsla_d[0..=2] {
-        if digits.is_empty() {
-            return Err(());
+// wrap in quotes, ready to emit as a rust raw str token, with enough hashes that the str can't
+// terminate the token early
+fn raw_str(content: &str) -> String {
+    let mut hashes = "####".to_string();
+    while content.contains(&format!("\"{}", hashes)) {
+        hashes.push('#');
+    }
+    format!("r{}\"{}\"{}", hashes, content, hashes)
+}
+
+// wrap in quotes, ready to emit as a rust byte str token, escaping any byte which can't appear
+// in it verbatim
+fn byte_str(content: &str) -> String {
+    let mut literal = "b\"".to_string();
+    for b in content.bytes() {
+        match b {
+            b'"' | b'\\' => literal.push_str(&format!("\\{}", b as char)),
+            b' '..=b'~' => literal.push(b as char),
+            _ => literal.push_str(&format!("\\x{:02x}", b)),
         }
     }
+    literal.push('"');
+    literal
+}
+
+// de-escape and trim the quotes from a str literal, returning its content
+fn literal_content(literal: &proc_macro::Literal) -> String {
+    let literal = literal.to_string();
 
-    let h = f32::from_str(&hsla_d[0]).or(Err(()))?;
-    if !(0.0..=360.0).contains(&h) {
-        return Err(());
+    // trim leading and trailing ".." or r#".."# from string literal
+    let start = &literal.find('\"').unwrap() + 1;
+    let end = &literal.rfind('\"').unwrap() - 1;
+    //bail if literal is empty
+    if start > end {
+        return String::new();
     }
-    let s = f32::from_str(&hsla_d[1]).or(Err(()))? / 100.0;
-    if !(0.0..=1.0).contains(&s) {
-        return Err(());
+    let content = &literal[start..=end];
+
+    // raw strings are passed through as written, so `\r` in a raw string remains a backslash and
+    // an `r`, while actual line breaks reach us as `\n` because rustc normalises `\r\n` in source
+    if literal.starts_with('r') {
+        content.to_string()
+    } else {
+        unescape(content)
     }
-    let l = f32::from_str(&hsla_d[2]).or(Err(()))? / 100.0;
-    if !(0.0..=1.0).contains(&l) {
-        return Err(());
+}
+
+// de-escape the content of a str literal which isn't raw, as the compiler would
+fn unescape(content: &str) -> String {
+    let mut output = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            output.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => output.push('\n'),
+            Some('r') => output.push('\r'),
+            Some('t') => output.push('\t'),
+            Some('0') => output.push('\0'),
+            Some(c @ ('\\' | '\'' | '"')) => output.push(c),
+            Some('x') => {
+                let hex: String = chars.by_ref().take(2).collect();
+                output.push(char::from(u8::from_str_radix(&hex, 16).unwrap()));
+            }
+            Some('u') => {
+                let hex: String = chars
+                    .by_ref()
+                    .skip(1)
+                    .take_while(|c| *c != '}')
+                    .filter(|c| *c != '_')
+                    .collect();
+                let code_point = u32::from_str_radix(&hex, 16).unwrap();
+                output.push(char::from_u32(code_point).unwrap());
+            }
+            // a line continuation skips the line break and any whitespace at the start of the
+            // next line
+            Some('\n') => {
+                while chars
+                    .next_if(|c| [' ', '\t', '\n', '\r'].contains(c))
+                    .is_some()
+                {}
+            }
+            // any other escape would have been rejected by the compiler
+            c => panic!(
+                "const_css_minify could not de-escape: \\{}",
+                c.unwrap_or_default()
+            ),
+        }
     }
+    output
+}
 
-    // weird algorithm from wikipedia...
-    let a = s * {
-        if l <= 0.5 {
-            l
-        } else {
-            1_f32 - l
+// check if we're dealing with path or literal, returning the css and, for a path, the directory
+// against which relative references are resolved. Paths are resolved relative to the crate root,
+// or failing that, relative to the source file invoking the macro.
+fn load_css(literal: String) -> (String, Option<PathBuf>) {
+    let mut path = PathBuf::from(&literal);
+    if !path.exists() {
+        let sibling = Span::call_site()
+            .local_file()
+            .and_then(|file| Some(file.parent()?.join(&path)));
+        if let Some(sibling) = sibling.filter(|s| s.exists()) {
+            path = sibling;
         }
-    };
-    let ks = [
-        (h / 30_f32) % 12_f32,
-        (8_f32 + h / 30_f32) % 12_f32,
-        (4_f32 + h / 30_f32) % 12_f32,
-    ];
-    for k in ks {
-        let c = match k {
-            ..=2_f32 => -1_f32,
-            2_f32..=4_f32 => k - 3_f32,
-            4_f32..=8_f32 => 1_f32,
-            8_f32..=10_f32 => 9_f32 - k,
-            10_f32.. => -1_f32,
-            _ => unreachable!(),
-        };
-        let integer = ((l - a * c) * 255_f32).round();
-        if integer < u8::MIN.into() || integer > u8::MAX.into() {
-            return Err(());
+    }
+    match fs::read_to_string(&path) {
+        Ok(css) => (
+            css,
+            Some(path.parent().unwrap_or(Path::new("")).to_path_buf()),
+        ),
+        // a file which exists but can't be decoded would otherwise be mistaken for a literal
+        Err(e) if e.kind() == ErrorKind::InvalidData => {
+            panic!(
+                "const_css_minify could not read {} as utf-8: {}",
+                literal, e
+            )
         }
-        let byte: u8 = unsafe { integer.to_int_unchecked() };
-        let hex = format!("{:04x}", byte).into_bytes();
-        //igore leading '0x' get only the actual hexadecimal digits
-        v.push(hex[2]);
-        v.push(hex[3]);
-    }
-
-    // alpha channel
-    if !hsla_d[3].is_empty() && !["1", "1.0", "100"].contains(&hsla_d[3].as_str()) {
-        let decimal = f32::from_str(&hsla_d[3]).or(Err(()))?;
-        let integer = if percents[3] {
-            (decimal * 255_f32 / 100_f32).round()
-        } else {
-            (decimal * 255_f32).round()
-        };
-        if integer < u8::MIN.into() || integer > u8::MAX.into() {
-            return Err(());
+        Err(_) => (literal, None),
+    }
+}
+
+// expand a glob pattern relative to the crate root, in which `*` matches any run of chars and `?`
+// any single char within a path component, returning the matching files sorted lexicographically
+fn expand_glob(pattern: &str) -> Vec<PathBuf> {
+    let root = if pattern.starts_with('/') { "/" } else { "" };
+    let mut paths = vec![PathBuf::from(root)];
+    for component in pattern.split('/').filter(|c| !c.is_empty()) {
+        if !component.contains(['*', '?']) {
+            paths.iter_mut().for_each(|path| path.push(component));
+            continue;
+        }
+        let mut matches = Vec::new();
+        for dir in paths {
+            let Ok(entries) = fs::read_dir(if dir.as_os_str().is_empty() {
+                Path::new(".")
+            } else {
+                &dir
+            }) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let name = entry.file_name();
+                if name
+                    .to_str()
+                    .is_some_and(|name| glob_match(component, name))
+                {
+                    matches.push(dir.join(name));
+                }
+            }
+        }
+        paths = matches;
+    }
+    paths.retain(|path| path.is_file());
+    paths.sort();
+    paths
+}
+
+// match a single path component against a glob pattern. Hidden files are only matched by a pattern
+// which itself starts with a `.`
+fn glob_match(pattern: &str, name: &str) -> bool {
+    if name.starts_with('.') && !pattern.starts_with('.') {
+        return false;
+    }
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    // position in the pattern and name just after the last `*`, to backtrack to on a mismatch
+    let mut star = None;
+    let (mut p, mut n) = (0, 0);
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                p += 1;
+                star = Some((p, n));
+            }
+            Some(c) if *c == '?' || *c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match star {
+                Some((star_p, star_n)) => {
+                    p = star_p;
+                    n = star_n + 1;
+                    star = Some((star_p, star_n + 1));
+                }
+                None => return false,
+            },
         }
-        let byte: u8 = unsafe { integer.to_int_unchecked() };
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
 
-        //format as hexadecimal
-        let hex = format!("{:04x}", byte).into_bytes();
-        //igore leading '0x' get only the actual hexadecimal digits
-        v.push(hex[2]);
-        v.push(hex[3]);
+fn minify_css(css: &str, options: &MinifyOptions) -> String {
+    let css = css.to_string();
+    let mut minifier = Minifier::new(options.clone());
+    minifier.minify_string(&css);
+    minifier.emit_error_msgs();
+    if std::env::var("CONST_CSS_MINIFY_STATS").is_ok_and(|v| v == "1") {
+        eprintln!("STATS const-css-minify: {}", minifier.size_stats());
     }
-    Ok(v)
+    minifier.get_output()
 }
 
+// more than enough for any sane stylesheet, and guards against import cycles
+const MAX_IMPORT_DEPTH: usize = 32;
+
 /*
- * requires input to start with "rgb(" or "rgba(" and end with ")"
+ * scans already minified css for `@import` statements and replaces each one with the minified
+ * content of the imported file. Relative references are relative to the file in which they appear,
+ * so `prefix` is the directory of the current file relative to the top-level file (which is itself
+ * in `dir`), and relative `url()`s are rewritten by joining them onto `prefix`.
+ *
+ * Imports with layer, supports or media conditions can't be inlined without changing their
+ * meaning, and an import left in place after inlined rules would be invalid, so we return None if
+ * any import reachable from css can't be inlined.
  */
-fn try_decode_rgb_func(input: &[u8]) -> Result<Vec<u8>, ()> {
-    let mut v = vec![b'#'];
-    let mut read = 3;
-    if input[read] == b'a' {
-        read += 1;
+fn inline_imports(
+    css: &str,
+    dir: &Path,
+    prefix: &Path,
+    options: &MinifyOptions,
+    depth: usize,
+) -> Option<String> {
+    if depth > MAX_IMPORT_DEPTH {
+        panic!("const_css_minify exceeded maximum @import depth, is there an import cycle?");
     }
-    if input[read] != b'(' {
-        return Err(());
-    }
-    read += 1;
-    let mut rgba_d = [
-        String::with_capacity(10),
-        String::with_capacity(10),
-        String::with_capacity(10),
-        String::with_capacity(10),
-    ];
-    let mut percents = [false, false, false, false];
-    let mut i = 0;
-    while input[read] != b')' {
+    let input = css.as_bytes();
+    let len = input.len();
+    let mut output = String::with_capacity(len);
+    let mut read = 0;
+    while read < len {
         match input[read] {
-            x if !RGB_FUNC_DECODABLE.contains(&x) => return Err(()),
-            d if d.is_ascii_digit() || d == b'.' => rgba_d[i].push(char::from(d)),
-            b'%' => percents[i] = true,
-            b' ' | b',' | b'/' => {
-                i += 1;
-                while [b' ', b',', b'/'].contains(&input[read + 1]) {
-                    read += 1;
+            q @ (b'"' | b'\'') => {
+                let end = input[read + 1..]
+                    .iter()
+                    .position(|b| *b == q)
+                    .map_or(len, |i| read + 1 + i + 1);
+                output.push_str(&css[read..end]);
+                read = end;
+            }
+            // an import may also follow a preserved comment, such as a license
+            b'@' if input[read..].starts_with(b"@import")
+                && (read == 0
+                    || [b';', b'}'].contains(&input[read - 1])
+                    || input[..read].ends_with(b"*/")) =>
+            {
+                let mut peek = read + b"@import".len();
+                if peek < len && input[peek] == b' ' {
+                    peek += 1;
+                }
+                let (target, _, consumed) = parse_url(&css[peek..])?;
+                if input.get(peek + consumed).is_some_and(|b| *b != b';')
+                    || !is_relative_url(target)
+                {
+                    return None;
+                }
+                let path = prefix.join(target);
+                let imported = fs::read_to_string(dir.join(&path)).unwrap_or_else(|e| {
+                    panic!(
+                        "const_css_minify failed to read @import {}: {}",
+                        dir.join(&path).display(),
+                        e
+                    )
+                });
+                let prefix = normalize_path(path.parent().unwrap_or(Path::new("")));
+                let imported = minify_css(&imported, options);
+                output.push_str(&inline_imports(
+                    &imported,
+                    dir,
+                    &prefix,
+                    options,
+                    depth + 1,
+                )?);
+                read = (peek + consumed + 1).min(len);
+            }
+            b'u' if input[read..].starts_with(b"url(")
+                && (read == 0 || !is_ident_byte(input[read - 1])) =>
+            {
+                match parse_url(&css[read..]) {
+                    Some((target, quote, consumed)) if is_relative_url(target) => {
+                        output.push_str("url(");
+                        output.extend(quote);
+                        output.push_str(&rewrite_url(target, prefix));
+                        output.extend(quote);
+                        output.push(')');
+                        read += consumed;
+                    }
+                    _ => {
+                        output.push('u');
+                        read += 1;
+                    }
                 }
             }
-            _ => unreachable!(), // did we add chars to RGB_FUNC_DECODABLE and not match here?
+            _ => {
+                let c = css[read..].chars().next().unwrap();
+                output.push(c);
+                read += c.len_utf8();
+            }
         }
-        read += 1;
     }
-    // check we got required input for r, g, b
-    for i in 0..=2 {
-        if rgba_d[i].is_empty() {
-            return Err(());
+    Some(output)
+}
+
+// parses a minified `url(..)` or quote string at the start of input, returning the target, the
+// quote char if quoted and the number of bytes consumed
+fn parse_url(input: &str) -> Option<(&str, Option<char>, usize)> {
+    let (inner, is_url) = match input.strip_prefix("url(") {
+        Some(inner) => (inner, true),
+        None => (input, false),
+    };
+    let (target, quote, consumed) = match inner.chars().next() {
+        Some(q @ ('"' | '\'')) => {
+            let end = inner[1..].find(q)? + 1;
+            (&inner[1..end], Some(q), end + 1)
         }
-        let byte: u8 = if percents[i] {
-            let decimal = f32::from_str(&rgba_d[i]).or(Err(()))?; // 👈 #unexpectedlisp
-            let integer = (decimal * 255_f32 / 100_f32).round();
-            if integer < u8::MIN.into() || integer > u8::MAX.into() {
-                return Err(());
-            }
-            unsafe { integer.to_int_unchecked() }
-        } else {
-            u8::from_str(&rgba_d[i]).or(Err(()))?
-        };
-        //format as hexadecimal
-        let hex = format!("{:04x}", byte).into_bytes();
-        //igore leading '0x' get only the actual hexadecimal digits
-        v.push(hex[2]);
-        v.push(hex[3]);
-    }
-    // alpha channel
-    if !rgba_d[3].is_empty() && !["1", "1.0", "100"].contains(&rgba_d[3].as_str()) {
-        let decimal = f32::from_str(&rgba_d[3]).or(Err(()))?;
-        let integer = if percents[3] {
-            (decimal * 255_f32 / 100_f32).round()
-        } else {
-            (decimal * 255_f32).round()
-        };
-        if integer < u8::MIN.into() || integer > u8::MAX.into() {
-            return Err(());
+        _ if is_url => {
+            let end = inner.find(')')?;
+            (&inner[..end], None, end)
         }
-        let byte: u8 = unsafe { integer.to_int_unchecked() };
+        _ => return None,
+    };
+    if !is_url {
+        return Some((target, quote, consumed));
+    }
+    if !inner[consumed..].starts_with(')') {
+        return None;
+    }
+    Some((target, quote, "url(".len() + consumed + 1))
+}
 
-        //format as hexadecimal
-        let hex = format!("{:04x}", byte).into_bytes();
-        //igore leading '0x' get only the actual hexadecimal digits
-        v.push(hex[2]);
-        v.push(hex[3]);
+// rewrites a relative url so it is relative to the top-level file rather than the file at prefix
+fn rewrite_url(url: &str, prefix: &Path) -> String {
+    if prefix == Path::new("") {
+        return url.to_string();
     }
-    Ok(v)
+    normalize_path(&prefix.join(url))
+        .to_string_lossy()
+        .replace('\\', "/")
 }
 
-fn try_minify_hex_color(input: &[u8]) -> Result<Vec<u8>, ()> {
-    let len = input.len();
-    if ![4, 5, 7, 9].contains(&len) || input[0] != b'#' {
-        return Err(());
+// true for relative references, i.e. not absolute paths, fragments, data uris or any other url
+// with a scheme
+fn is_relative_url(url: &str) -> bool {
+    if url.is_empty() || url.starts_with('/') || url.starts_with('#') {
+        return false;
     }
-    let mut v = vec![b'#'];
-    for byte in &input[1..] {
-        if !byte.is_ascii_hexdigit() {
-            return Err(());
+    let scheme_len = url
+        .bytes()
+        .take_while(|b| b.is_ascii_alphanumeric() || [b'+', b'-', b'.'].contains(b))
+        .count();
+    !(scheme_len > 0 && url[scheme_len..].starts_with(':'))
+}
+
+// lexically resolves `.` and `..` components, without touching the filesystem
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => (),
+            Component::ParentDir
+                if normalized
+                    .components()
+                    .next_back()
+                    .is_some_and(|c| c != Component::ParentDir) =>
+            {
+                normalized.pop();
+            }
+            c => normalized.push(c),
         }
-        v.push(*byte);
-    }
-    if len == 9 && v[1] == v[2] && v[3] == v[4] && v[5] == v[6] && v[7] == v[8] {
-        v.remove(8);
-        v.remove(6);
-        v.remove(4);
-        v.remove(2);
-    }
-    if len == 7 && v[1] == v[2] && v[3] == v[4] && v[5] == v[6] {
-        v.remove(6);
-        v.remove(4);
-        v.remove(2);
     }
-    Ok(v)
+    normalized
 }
@@ -0,0 +1,5 @@
+minify!(r#"
+.a {
+    color: red;
+}
+"#)
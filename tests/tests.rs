@@ -1,6 +1,9 @@
 #[cfg(test)]
 mod tests {
-    use const_css_minify::minify;
+    use const_css_minify::{
+        minify, minify_bytes, minify_fragment, minify_js_template, minify_media,
+        minify_passthrough, minify_strict,
+    };
 
     /*
      * does not compile (with help message), which is the desired behaviour.
@@ -111,6 +114,37 @@ mod tests {
         assert_eq!(minify!("div { span {margin:1px}}"), "div{span{margin:1px}}");
     }
 
+    #[test]
+    fn deeply_nested_classes() {
+        assert_eq!(
+            minify!(
+                r#"
+                .a { color: #ffffff;
+                  .b { margin: 0px;
+                    .c {
+                      .d { padding : 1px 2px ;
+                        .e:hover {
+                          .f .g {
+                            .h {
+                              & .i { font-weight: bold;
+                                .j {
+                                  .k { color: #ffffff; }
+                                }
+                              }
+                            }
+                          }
+                        }
+                      }
+                    }
+                  }
+                }
+                "#
+            ),
+            ".a{color:#fff;.b{margin:0;.c{.d{padding:1px 2px;.e:hover{.f .g{.h{& .i{font-weight:700;\
+            .j{.k{color:#fff}}}}}}}}}}"
+        );
+    }
+
     #[test]
     fn hexcode_colors() {
         assert_eq!(minify!("#{color:#000}"), "#{color:#000}");
@@ -121,6 +155,23 @@ mod tests {
         assert_eq!(minify!("#{color:#aabbb}"), "#{color:#aabbb}");
         assert_eq!(minify!("#{color:#DDEEFFF}"), "#{color:#DDEEFFF}");
         assert_eq!(minify!("#{color:#aabbccddd}"), "#{color:#aabbccddd}");
+        // hex digits are lowercased
+        assert_eq!(minify!("#{color:#ABC}"), "#{color:#abc}");
+        assert_eq!(minify!("#{color:#AABBCC}"), "#{color:#abc}");
+        assert_eq!(minify!("#{color:#4CAF50}"), "#{color:#4caf50}");
+        assert_eq!(minify!("#{color:#DeFa}"), "#{color:#defa}");
+        // but not in id selectors, which are case-sensitive
+        assert_eq!(minify!("#ABCDEF{color:#ABCDEF}"), "#ABCDEF{color:#abcdef}");
+        assert_eq!(minify!("#AABBCC .x{color:red}"), "#AABBCC .x{color:red}");
+        // even after a pseudo class, whose colon isn't an assignment
+        assert_eq!(
+            minify!("a:hover #ABCDEF{color:red}a:hover #AABBCC .x{color:#AABBCC}"),
+            "a:hover #ABCDEF{color:red}a:hover #AABBCC .x{color:#abc}"
+        );
+        assert_eq!(
+            minify!("a{color:red;&:hover #AABBCC{color:red}}"),
+            "a{color:red;&:hover #AABBCC{color:red}}"
+        );
     }
 
     #[test]
@@ -202,4 +253,1998 @@ mod tests {
             include_str!("./w3_expected.css").to_string()
         );
     }
+
+    #[test]
+    fn shorthand_colors() {
+        assert_eq!(
+            minify!("#{border: 1px solid #ffffff}"),
+            "#{border:1px solid #fff}"
+        );
+        assert_eq!(
+            minify!("#{outline: 2px dashed rgb(0,0,0)}"),
+            "#{outline:2px dashed #000}"
+        );
+        assert_eq!(
+            minify!("#{box-shadow: 0 0 4px 2px rgba(0, 0, 0, 0.2)}"),
+            "#{box-shadow:0 0 4px 2px #0003}"
+        );
+        assert_eq!(
+            minify!("#{box-shadow: inset 1px 1px #aabbcc, 2px 2px hsl(0, 0%, 100%)}"),
+            "#{box-shadow:inset 1px 1px #abc,2px 2px #fff}"
+        );
+    }
+
+    #[test]
+    fn collapse_zeros() {
+        assert_eq!(minify!(collapse_zeros, "#{margin: 0 0 0 0}"), "#{margin:0}");
+        assert_eq!(
+            minify!(collapse_zeros, "#{padding: 0px 0%; inset: 0 0.0em}"),
+            "#{padding:0;inset:0}"
+        );
+        // only margins and paddings have a zero percentage collapsed
+        assert_eq!(
+            minify!(collapse_zeros, "#{inset: 0 0%; border-radius: 0% 0}"),
+            "#{inset:0 0%;border-radius:0% 0}"
+        );
+        assert_eq!(
+            minify!(collapse_zeros, "#{margin: 0 auto}"),
+            "#{margin:0 auto}"
+        );
+        assert_eq!(
+            minify!(collapse_zeros, "#{margin: 0 0 0 0 !important}"),
+            "#{margin:0 0 0 0!important}"
+        );
+        assert_eq!(minify!(collapse_zeros, "#{flex: 0 0}"), "#{flex:0 0}");
+        // option is opt-in
+        assert_eq!(minify!("#{margin: 0 0 0 0}"), "#{margin:0 0 0 0}");
+    }
+
+    #[test]
+    fn tab_indentation() {
+        const RESULT: &str = minify!("./tests/tabs.css");
+        assert_eq!(
+            RESULT,
+            "div p,.button:hover{margin:1px 2px;font-family:\"Tab\tSeparated\",serif}"
+        );
+        // the only tab to survive is the one inside the quote string
+        assert_eq!(RESULT.matches('\t').count(), 1);
+    }
+
+    #[test]
+    fn inline_imports() {
+        assert_eq!(
+            minify!(inline_imports, "./tests/imports/main.css"),
+            concat!(
+                "html{background:url(base.png)}",
+                ".logo{background:url(parts/logo.png) no-repeat}",
+                ".icon{background:url(parts/icons/icon.svg),url(/abs.png),url(#sprite)}",
+                "body{background:url(./img/bg.png)}",
+            )
+        );
+        // preserved comments and a charset may come before the imports
+        assert_eq!(
+            minify!(inline_imports, "./tests/imports/licensed.css"),
+            concat!(
+                "/*! license */@charset \"utf-8\";/*! imports */",
+                "html{background:url(base.png)}.licensed{color:red}",
+            )
+        );
+        // a conditional import can't be inlined, so all imports are left in place
+        assert_eq!(
+            minify!(inline_imports, "./tests/imports/conditional.css"),
+            "@import \"./base.css\";@import url(./parts/theme.css) print;"
+        );
+        // option is opt-in
+        assert_eq!(
+            minify!("./tests/imports/main.css"),
+            "@import \"./parts/theme.css\";body{background:url(./img/bg.png)}"
+        );
+    }
+
+    #[test]
+    fn keyword_values() {
+        assert_eq!(
+            minify!("#{contain: layout paint; content-visibility: auto}"),
+            "#{contain:layout paint;content-visibility:auto}"
+        );
+        assert_eq!(
+            minify!("#{grid-row: span 2; grid-column: 2}"),
+            "#{grid-row:span 2;grid-column:2}"
+        );
+        assert_eq!(
+            minify!("#{font-variant-numeric: oldstyle-nums; z-index: 10; order: 0}"),
+            "#{font-variant-numeric:oldstyle-nums;z-index:10;order:0}"
+        );
+        assert_eq!(
+            minify!("#{animation-name: fade-2x; columns: 3 auto}"),
+            "#{animation-name:fade-2x;columns:3 auto}"
+        );
+    }
+
+    #[test]
+    fn important_with_var() {
+        assert_eq!(
+            minify!("#{color: var(--c) !important}"),
+            "#{color:var(--c)!important}"
+        );
+        assert_eq!(
+            minify!("#{margin: var(--m, 0) !important; padding: 1px}"),
+            "#{margin:var(--m,0)!important;padding:1px}"
+        );
+        assert_eq!(
+            minify!("#{--c: #ffffff !important}"),
+            "#{--c:#fff!important}"
+        );
+    }
+
+    /*
+     * minify_assert_eq! fails compilation when the outputs differ, so it is exercised with trybuild
+     */
+    #[test]
+    fn assert_eq_ui() {
+        let t = trybuild::TestCases::new();
+        t.pass("tests/ui/assert_eq_pass.rs");
+        t.compile_fail("tests/ui/assert_eq_fail.rs");
+        t.compile_fail("tests/ui/fragment_escape_fail.rs");
+        t.compile_fail("tests/ui/fragment_fail.rs");
+        t.compile_fail("tests/ui/invalid_utf8.rs");
+        t.compile_fail("tests/ui/glob_empty.rs");
+        t.compile_fail("tests/ui/media_missing_file.rs");
+        t.compile_fail("tests/ui/missing_file.rs");
+        t.compile_fail("tests/ui/strict_fail.rs");
+        t.compile_fail("tests/ui/to_file_no_out_dir.rs");
+        t.compile_fail("tests/ui/to_file_outside.rs");
+    }
+
+    #[test]
+    fn empty_property_name() {
+        //should not panic
+        assert_eq!(minify!("a{ :red }"), "a{:red}");
+        assert_eq!(minify!("a{ : red; color: red }"), "a{:red;color:red}");
+        assert_eq!(minify!("a{ ;color:red }"), "a{color:red}");
+        assert_eq!(minify!("a{:}"), "a{:}");
+    }
+
+    #[test]
+    fn lossless_alpha() {
+        assert_eq!(
+            minify!(lossless_alpha, "#{color:rgba(0, 0, 0, 0.2)}"),
+            "#{color:#0003}"
+        );
+        assert_eq!(
+            minify!(lossless_alpha, "#{color:rgba(0, 0, 0, 0.333)}"),
+            "#{color:rgba(0,0,0,.333)}"
+        );
+        assert_eq!(
+            minify!(lossless_alpha, "#{color:rgb(0 0 0 / 0.5)}"),
+            "#{color:rgb(0 0 0/.5)}"
+        );
+        assert_eq!(
+            minify!(lossless_alpha, "#{color:hsla(0, 0%, 100%, 40%)}"),
+            "#{color:#fff6}"
+        );
+        assert_eq!(
+            minify!(lossless_alpha, "#{color:hsla(0, 0%, 100%, 0.7)}"),
+            "#{color:hsla(0,0%,100%,.7)}"
+        );
+        // opaque colors are unaffected
+        assert_eq!(
+            minify!(lossless_alpha, "#{color:rgba(255, 255, 255, 1)}"),
+            "#{color:#fff}"
+        );
+        // option is opt-in
+        assert_eq!(minify!("#{color:rgba(0, 0, 0, 0.333)}"), "#{color:#0005}");
+    }
+
+    #[test]
+    fn page_inside_media() {
+        assert_eq!(
+            minify!("@media print { @page { margin: 1cm } .a { color:#000000 } }"),
+            "@media print{@page{margin:1cm}.a{color:#000}}"
+        );
+        assert_eq!(
+            minify!("@media print { @page :first { margin-top: 2cm; } .a { color:#000000; } }"),
+            "@media print{@page :first{margin-top:2cm}.a{color:#000}}"
+        );
+    }
+
+    #[test]
+    fn file_leading_trailing_whitespace() {
+        assert_eq!(minify!("./tests/blank_lines.css"), ".a{color:red}");
+        assert_eq!(minify!("./tests/only_blank_lines.css"), "");
+    }
+
+    #[test]
+    fn concatenation() {
+        const A: &str = minify_fragment!("@import 'base.css'; a { color: #ffffff; }");
+        const B: &str = minify_fragment!(".b > .c { margin: 0 }");
+        assert_eq!(
+            A.to_string() + B,
+            minify!("@import 'base.css'; a { color: #ffffff; } .b > .c { margin: 0 }")
+        );
+        assert_eq!(minify_fragment!(""), "");
+        // escaped quotes and braces neither open nor close anything
+        assert_eq!(
+            minify_fragment!(r#"a { content: "\"" }"#),
+            r#"a{content:"\""}"#
+        );
+        assert_eq!(
+            minify_fragment!(r#".a\{ { content: '\'{' }"#),
+            r#".a\{{content:'\'{'}"#
+        );
+        // an incomplete fragment runs together with whatever follows it, which minify_fragment!
+        // would have rejected
+        assert_eq!(minify!(".a").to_string() + minify!(".b{}"), ".a.b{}");
+        assert_eq!(minify!(".a .b{}"), ".a .b{}");
+    }
+
+    #[test]
+    fn urls() {
+        assert_eq!(
+            minify!("a{background: url( 'a b.png' ) no-repeat}"),
+            "a{background:url('a b.png') no-repeat}"
+        );
+        assert_eq!(
+            minify!("a{background: URL(a.png), url(b.png)}"),
+            "a{background:URL(a.png),url(b.png)}"
+        );
+    }
+
+    #[test]
+    fn unclosed_urls() {
+        //should not panic
+        assert_eq!(
+            minify!("a{background:url(unclosed"),
+            "a{background:url(unclosed"
+        );
+        assert_eq!(
+            minify!("a{background:url(\"unclosed"),
+            "a{background:url(\"unclosed"
+        );
+        assert_eq!(minify!("a{background:url("), "a{background:url(");
+        // a preserved comment in a url may contain quotes and parens
+        assert_eq!(minify!("a{b:url(x/*!'*/)}"), "a{b:url(x/*!'*/)}");
+        assert_eq!(minify!("url(/*!'#*/:"), "url(/*!'#*/:");
+        assert_eq!(minify!("url(/*!@media )\"*/]"), "url(/*!@media )\"*/]");
+    }
+
+    #[test]
+    fn url_fragment_references() {
+        assert_eq!(
+            minify!("a{clip-path: url(#myClip)}"),
+            "a{clip-path:url(#myClip)}"
+        );
+        assert_eq!(minify!("a{mask: url(#m)}"), "a{mask:url(#m)}");
+        // fragment ids that look like hex colors must not be minified
+        assert_eq!(
+            minify!("a{fill: url(#aabbcc) #aabbcc}"),
+            "a{fill:url(#aabbcc) #abc}"
+        );
+        assert_eq!(
+            minify!("a{filter: url('#ffffff')}"),
+            "a{filter:url(#ffffff)}"
+        );
+    }
+
+    #[test]
+    fn quiet() {
+        // warnings are not emitted, but the output is identical
+        assert_eq!(minify!(quiet, "a{content:'unclosed"), "a{content:'unclosed");
+        assert_eq!(
+            minify!(quiet, "a{margin:1px /*unclosed"),
+            minify!("a{margin:1px /*unclosed")
+        );
+        // and the errors are still collected
+        let options = const_css_minify_core::MinifyOptions {
+            quiet: true,
+            ..Default::default()
+        };
+        let mut minifier = const_css_minify_core::Minifier::new(options);
+        minifier.minify_string("a{content:'unclosed");
+        minifier.emit_error_msgs();
+        let errors = minifier.take_errors();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].to_string(),
+            "reached end of input while inside quote string"
+        );
+    }
+
+    #[test]
+    fn global_keywords() {
+        assert_eq!(minify!("a{ all: initial }"), "a{all:initial}");
+        assert_eq!(minify!("a{ all: unset; }"), "a{all:unset}");
+        assert_eq!(
+            minify!("a{ display: revert-layer; color: revert; margin: inherit }"),
+            "a{display:revert-layer;color:revert;margin:inherit}"
+        );
+        assert_eq!(
+            minify!(
+                collapse_zeros,
+                "a{ all: unset; margin: initial; padding: 0 0 }"
+            ),
+            "a{all:unset;margin:initial;padding:0}"
+        );
+    }
+
+    #[test]
+    fn nth_pseudo_classes() {
+        assert_eq!(minify!("li:nth-child( 2n + 1 ) {}"), "li:nth-child(2n+1){}");
+        assert_eq!(
+            minify!("li:nth-last-of-type( -n + 3 ) {}"),
+            "li:nth-last-of-type(-n+3){}"
+        );
+        assert_eq!(
+            minify!(":nth-child( 2n + 1 of .foo , .bar ) {}"),
+            ":nth-child(2n+1 of .foo,.bar){}"
+        );
+        assert_eq!(
+            minify!(":nth-child(2n+1 of .a,.b) {}"),
+            ":nth-child(2n+1 of .a,.b){}"
+        );
+        assert_eq!(
+            minify!("ul :nth-child( odd of li ) {}"),
+            "ul :nth-child(odd of li){}"
+        );
+        assert_eq!(minify!("a{b:nth-child(2)}"), "a{b:nth-child(2)}");
+    }
+
+    #[test]
+    fn media() {
+        assert_eq!(
+            minify_media! {
+                "screen" => "tests/media/screen.css",
+                "print" => "tests/media/print.css"
+            },
+            "@media screen{.nav{display:flex;color:#fff}}@media print{.nav{display:none}body{color:#000}}"
+        );
+        assert_eq!(
+            minify_media! { "(max-width: 600px)" => "./tests/media/print.css", },
+            "@media (max-width:600px){.nav{display:none}body{color:#000}}"
+        );
+    }
+
+    #[test]
+    fn carriage_returns() {
+        // a source file checked out with windows line endings
+        assert_eq!(
+            minify!("tests/crlf/style.css"),
+            ".a{margin:1px 2px}.b{color:#fff}"
+        );
+        // a raw string spanning windows line endings in rust source
+        const RAW: &str = include!("crlf/raw.rs");
+        assert_eq!(RAW, ".a{color:red}");
+        assert!(!RAW.contains('\r'));
+        // a raw string is not de-escaped, so `\r` is passed through as a backslash and an `r`
+        assert_eq!(minify!(r#"a{content:"\r"}"#), r#"a{content:"\r"}"#);
+        // whereas an escaped carriage return in a normal string is whitespace
+        assert_eq!(minify!("a{\r\ncolor:red}"), "a{color:red}");
+    }
+
+    #[test]
+    fn font_family() {
+        assert_eq!(
+            minify!(r#"body { font-family: "Helvetica Neue" , Arial , sans-serif ; }"#),
+            r#"body{font-family:"Helvetica Neue",Arial,sans-serif}"#
+        );
+        // quotes are never removed, and spaces inside them are preserved
+        assert_eq!(
+            minify!(r#"p { font-family: 'Times  New Roman', "Arial", serif }"#),
+            r#"p{font-family:'Times  New Roman',"Arial",serif}"#
+        );
+        // hyphenated generic families are not mistaken for anything else
+        assert_eq!(
+            minify!("code { font-family: ui-monospace, monospace, system-ui }"),
+            "code{font-family:ui-monospace,monospace,system-ui}"
+        );
+    }
+
+    #[test]
+    fn partially_literal_colors() {
+        // a color is only decoded if every channel is a literal, otherwise it is left verbatim
+        assert_eq!(minify!("a{color:rgb(255 128 0)}"), "a{color:#ff8000}");
+        assert_eq!(
+            minify!("a{color:rgb(255 var(--g) 0)}"),
+            "a{color:rgb(255 var(--g) 0)}"
+        );
+        assert_eq!(
+            minify!("a{color:rgb(255 calc(1*2) 0)}"),
+            "a{color:rgb(255 calc(1*2) 0)}"
+        );
+        assert_eq!(
+            minify!("a{color:hsl(var(--h) 50% 50%)}"),
+            "a{color:hsl(var(--h) 50% 50%)}"
+        );
+        assert_eq!(
+            minify!("a{color:rgba(255, 128, 0, var(--a))}"),
+            "a{color:rgba(255,128,0,var(--a))}"
+        );
+        // input ending inside a color function is left verbatim
+        assert_eq!(minify!("a{color:rgb(255 128 0"), "a{color:rgb(255 128 0");
+        assert_eq!(
+            minify!("a{color:hsl(180 50% 50%"),
+            "a{color:hsl(180 50% 50%"
+        );
+    }
+
+    #[test]
+    fn discrete_media_features() {
+        assert_eq!(
+            minify!("@media (prefers-color-scheme: dark) { a { color: #ffffff } }"),
+            "@media (prefers-color-scheme:dark){a{color:#fff}}"
+        );
+        assert_eq!(
+            minify!("@media (prefers-reduced-motion: no-preference) { a { transition: none } }"),
+            "@media (prefers-reduced-motion:no-preference){a{transition:none}}"
+        );
+        assert_eq!(
+            minify!("@media screen and (update: fast) and (overflow-block: scroll) { a { b: c } }"),
+            "@media screen and (update:fast) and (overflow-block:scroll){a{b:c}}"
+        );
+        assert_eq!(
+            minify!("@media not (hover: none) , (pointer: coarse) { a { b: c } }"),
+            "@media not (hover:none),(pointer:coarse){a{b:c}}"
+        );
+    }
+
+    #[test]
+    fn preference_media_features() {
+        assert_eq!(
+            minify!("@media (prefers-reduced-data: reduce) { a { background: none } }"),
+            "@media (prefers-reduced-data:reduce){a{background:none}}"
+        );
+        assert_eq!(
+            minify!("@media (prefers-contrast: more) { a { color: #000000 } }"),
+            "@media (prefers-contrast:more){a{color:#000}}"
+        );
+        assert_eq!(
+            minify!("@media ( forced-colors : active ) { a { forced-color-adjust: none } }"),
+            "@media (forced-colors:active){a{forced-color-adjust:none}}"
+        );
+        assert_eq!(
+            minify!(
+                "@media (prefers-reduced-transparency: reduce) and (inverted-colors: inverted) {
+                    a { opacity: 1 }
+                }"
+            ),
+            "@media (prefers-reduced-transparency:reduce) and (inverted-colors:inverted)\
+            {a{opacity:1}}"
+        );
+    }
+
+    #[test]
+    fn template_placeholders() {
+        assert_eq!(minify!("a { color: __COLOR__; }"), "a{color:__COLOR__}");
+        assert_eq!(
+            minify!("a { margin: __GAP__ 0 ; font: __SIZE__/1.5 __FAMILY__ }"),
+            "a{margin:__GAP__ 0;font:__SIZE__/1.5 __FAMILY__}"
+        );
+        // brace delimited placeholders don't corrupt the enclosing block
+        assert_eq!(minify!("a { color: {{primary}} }"), "a{color:{{primary}}}");
+        assert_eq!(
+            minify!("a { color: {{ primary }}; } b { color: ${secondary} }"),
+            "a{color:{{primary}}}b{color:${secondary}}"
+        );
+    }
+
+    #[test]
+    fn passthrough() {
+        assert_eq!(
+            minify_passthrough!("./tests/tabs.css"),
+            include_str!("tabs.css")
+        );
+        assert_eq!(
+            minify_passthrough!(collapse_zeros, "a { margin: 0 0; }"),
+            "a { margin: 0 0; }"
+        );
+        // normal strings are de-escaped just as they are for minify!()
+        assert_eq!(
+            minify_passthrough!("a {\n\tcolor: red;\n}"),
+            "a {\n\tcolor: red;\n}"
+        );
+    }
+
+    #[test]
+    fn malformed_media_queries() {
+        // odd or invalid preludes are collapsed conservatively, and the block that follows is
+        // always found intact
+        assert_eq!(
+            minify!("@media ((min-width: 1px) , (max-width: 2px)) { a { b: c } }"),
+            "@media ((min-width:1px),(max-width:2px)){a{b:c}}"
+        );
+        assert_eq!(
+            minify!("@media (((( color )))) { a { b: c } }"),
+            "@media ((((color)))){a{b:c}}"
+        );
+        assert_eq!(
+            minify!("@media screen and , (color) { a { b: c } }"),
+            "@media screen and,(color){a{b:c}}"
+        );
+        assert_eq!(minify!("@media ( ) { a { b: c } }"), "@media (){a{b:c}}");
+        assert_eq!(
+            minify!("@media (min-width: 1px)) { a { b: c } }"),
+            "@media (min-width:1px)){a{b:c}}"
+        );
+        assert_eq!(
+            minify!("@media ((min-width: 1px) { a { b: c } }"),
+            "@media ((min-width:1px){a{b:c}}"
+        );
+        assert_eq!(
+            minify!("@media (width >= 600px) and not ( hover : hover ) { a { b: c } }"),
+            "@media (width>=600px) and not (hover:hover){a{b:c}}"
+        );
+        assert_eq!(
+            minify!("@media screen and (min-width:) { a { b: c } }"),
+            "@media screen and (min-width:){a{b:c}}"
+        );
+        assert_eq!(minify!("@media : { a { b: c } }"), "@media :{a{b:c}}");
+        assert_eq!(minify!("@media (:) { a { b: c } }"), "@media (:){a{b:c}}");
+        assert_eq!(minify!("@media ! { a { b: c } }"), "@media !{a{b:c}}");
+        assert_eq!(minify!("@media , , { a { b: c } }"), "@media,,{a{b:c}}");
+        assert_eq!(minify!("@media ("), "@media (");
+    }
+
+    #[test]
+    fn not_media_query_list() {
+        // `not` applies only to the first query in the list
+        assert_eq!(
+            minify!("@media not screen , print { a { b: c } }"),
+            "@media not screen,print{a{b:c}}"
+        );
+        assert_eq!(
+            minify!("@media not  screen and (color) , print { a { b: c } }"),
+            "@media not screen and (color),print{a{b:c}}"
+        );
+    }
+
+    #[test]
+    fn keyframe_selector_lists() {
+        assert_eq!(
+            minify!("@keyframes x { 0% , 50% { opacity: 0 } 100% { opacity : 1 } }"),
+            "@keyframes x{0%,50%{opacity:0}100%{opacity:1}}"
+        );
+        assert_eq!(
+            minify!("@keyframes fade { from , to { opacity: 0 } 25% , 75%{ opacity: .5 } }"),
+            "@keyframes fade{from,to{opacity:0}25%,75%{opacity:.5}}"
+        );
+    }
+
+    #[test]
+    fn short_hex_alpha() {
+        // collapses to 4 digits only when each of the four channels is a repeated hex digit
+        assert_eq!(
+            minify!("a{color:rgba(255,255,255,0.533)}"),
+            "a{color:#fff8}"
+        );
+        assert_eq!(
+            minify!("a{color:rgba(255,255,255,53.3%)}"),
+            "a{color:#fff8}"
+        );
+        assert_eq!(
+            minify!("a{color:rgba(255,255,255,0.067)}"),
+            "a{color:#fff1}"
+        );
+        assert_eq!(minify!("a{color:rgba(255,255,255,0.2)}"), "a{color:#fff3}");
+        assert_eq!(minify!("a{color:rgba(255,255,255,0.4)}"), "a{color:#fff6}");
+        assert_eq!(minify!("a{color:rgba(255,255,255,0.6)}"), "a{color:#fff9}");
+        assert_eq!(minify!("a{color:rgba(255,255,255,0)}"), "a{color:#fff0}");
+        // an opaque alpha is dropped entirely
+        assert_eq!(minify!("a{color:rgba(255,255,255,1)}"), "a{color:#fff}");
+        // alpha is not a repeated digit
+        assert_eq!(
+            minify!("a{color:rgba(255,255,255,0.5)}"),
+            "a{color:#ffffff80}"
+        );
+        assert_eq!(
+            minify!("a{color:rgba(255,255,255,0.25)}"),
+            "a{color:#ffffff40}"
+        );
+        // a color channel is not a repeated digit
+        assert_eq!(
+            minify!("a{color:rgba(255,255,254,0.533)}"),
+            "a{color:#fffffe88}"
+        );
+    }
+
+    #[test]
+    fn custom_media() {
+        assert_eq!(
+            minify!("@custom-media --narrow (max-width : 30em) ;"),
+            "@custom-media --narrow (max-width:30em);"
+        );
+        assert_eq!(
+            minify!("@custom-media --narrow (max-width: 30em); @media (--narrow) { a { b: c } }"),
+            "@custom-media --narrow (max-width:30em);@media (--narrow){a{b:c}}"
+        );
+        assert_eq!(
+            minify!("@custom-selector :--heading h1 , h2 , h3;"),
+            "@custom-selector :--heading h1,h2,h3;"
+        );
+        assert_eq!(
+            minify!("@custom-selector :--heading h1; :--heading :hover { b: c }"),
+            "@custom-selector :--heading h1;:--heading :hover{b:c}"
+        );
+        assert_eq!(
+            minify!("@supports (display : grid) and selector(a :hover) { a { b: c } }"),
+            "@supports (display:grid) and selector(a :hover){a{b:c}}"
+        );
+    }
+
+    #[test]
+    fn documented_const() {
+        minify!(CSS => "./tests/tabs.css");
+        assert_eq!(CSS, minify!("./tests/tabs.css"));
+        minify!(pub(crate) COLLAPSED => collapse_zeros, "a { margin: 0 0; }");
+        assert_eq!(COLLAPSED, "a{margin:0}");
+    }
+
+    #[test]
+    fn slash_separators() {
+        assert_eq!(
+            minify!("a { border-radius: 10px / 20px; background: center / cover }"),
+            "a{border-radius:10px/20px;background:center/cover}"
+        );
+        assert_eq!(
+            minify!("a { aspect-ratio: 16 / 9; font: 12px / 1.5 serif; grid-area: 1 / 2 / 3 }"),
+            "a{aspect-ratio:16/9;font:12px/1.5 serif;grid-area:1/2/3}"
+        );
+        assert_eq!(
+            minify!("@media (min-aspect-ratio: 16 / 9) { a { b: c } }"),
+            "@media (min-aspect-ratio:16/9){a{b:c}}"
+        );
+        // urls, functions and colors
+        assert_eq!(
+            minify!("a { background: url( a / b.png ) center / cover }"),
+            "a{background:url(a / b.png) center/cover}"
+        );
+        assert_eq!(
+            minify!("a { width: calc(100% / 3); color: rgb(var(--c) / 50%) }"),
+            "a{width:calc(100%/3);color:rgb(var(--c)/50%)}"
+        );
+        assert_eq!(
+            minify!("a { color: rgb(0 0 0 / 50%) }"),
+            "a{color:#00000080}"
+        );
+        // quotes are untouched
+        assert_eq!(
+            minify!(r#"a { grid-area: 1 / 2; content: "a / b" }"#),
+            r#"a{grid-area:1/2;content:"a / b"}"#
+        );
+    }
+
+    #[test]
+    fn grid_line_names() {
+        assert_eq!(
+            minify!(
+                "a { grid-template-columns: [full-start] 1fr  [main-start] 2fr [main-end] 1fr [full-end] }"
+            ),
+            "a{grid-template-columns:[full-start] 1fr [main-start] 2fr [main-end] 1fr [full-end]}"
+        );
+        assert_eq!(
+            minify!("a { grid-template-columns: repeat(2, [col-start] 1fr [col-end]) [end] }"),
+            "a{grid-template-columns:repeat(2,[col-start] 1fr [col-end]) [end]}"
+        );
+        // several names in one pair of brackets are separated by spaces
+        assert_eq!(
+            minify!(
+                "a { grid-template-rows: [header-start  top] auto [header-end main-start] 1fr }"
+            ),
+            "a{grid-template-rows:[header-start top] auto [header-end main-start] 1fr}"
+        );
+    }
+
+    #[test]
+    fn strip_empty_declarations() {
+        assert_eq!(
+            minify!(strip_empty_declarations, "a { color: ; margin: 0 }"),
+            "a{margin:0}"
+        );
+        assert_eq!(
+            minify!(strip_empty_declarations, "a{margin:0;color:}b{color:;}"),
+            "a{margin:0}b{}"
+        );
+        assert_eq!(
+            minify!(strip_empty_declarations, "a{color:;;margin:0;padding:}"),
+            "a{margin:0}"
+        );
+        // custom properties may validly be empty
+        assert_eq!(
+            minify!(strip_empty_declarations, "a{--x:;--y: ;color:red}"),
+            "a{--x:;--y:;color:red}"
+        );
+        assert_eq!(minify!("a{color:;margin:0}"), "a{color:;margin:0}");
+    }
+
+    #[test]
+    fn import_conditions() {
+        assert_eq!(
+            minify!(
+                "@import url( theme.css ) layer( framework.theme ) supports( selector( & ) ) ( min-width : 400px ) ;"
+            ),
+            "@import url(theme.css) layer(framework.theme) supports(selector(&)) (min-width:400px);"
+        );
+        assert_eq!(
+            minify!(
+                "@import 'theme.css'  layer(base)  supports( display : grid ) screen and ( min-width: 400px ) , print ;"
+            ),
+            "@import 'theme.css' layer(base) supports(display:grid) screen and (min-width:400px),print;"
+        );
+        assert_eq!(
+            minify!("@import url(a.css) supports(selector(a :hover)) ; a { b: c }"),
+            "@import url(a.css) supports(selector(a :hover));a{b:c}"
+        );
+    }
+
+    #[test]
+    fn nested_functional_pseudo_classes() {
+        assert_eq!(
+            minify!(":is( .a:not( .b , .c ) , .d ) { e: f }"),
+            ":is(.a:not(.b,.c),.d){e:f}"
+        );
+        assert_eq!(
+            minify!("ul :where( .x :has( .y , .z ) , :is( .a:not( .b ) .c ) ) li { e: f }"),
+            "ul :where(.x :has(.y,.z),:is(.a:not(.b) .c)) li{e:f}"
+        );
+        assert_eq!(
+            minify!("a:not( :is( .b , :where( .c .d , .e ) ) ) :hover { e: f }"),
+            "a:not(:is(.b,:where(.c .d,.e))) :hover{e:f}"
+        );
+    }
+
+    #[test]
+    fn byte_order_mark() {
+        const RESULT: &str = minify!("./tests/bom_charset.css");
+        assert_eq!(RESULT, "@charset \"utf-8\";.a{content:\"é\"}");
+        assert!(RESULT.starts_with("@charset \"utf-8\";"));
+    }
+
+    #[test]
+    fn js_template() {
+        assert_eq!(
+            minify_js_template!(r#"a::before { content: "`quoted`"; }"#),
+            r#"a::before{content:"\`quoted\`"}"#
+        );
+        assert_eq!(
+            minify_js_template!(r#"a::before { content: "${x} $ {" }"#),
+            r#"a::before{content:"\${x} $ {"}"#
+        );
+        // css escapes keep their backslash once the template literal is evaluated
+        assert_eq!(
+            minify_js_template!(r#"a::before { content: "\201C" }"#),
+            r#"a::before{content:"\\201C"}"#
+        );
+        assert_eq!(
+            minify_js_template!(collapse_zeros, "a { margin: 0 0; color: #ffffff }"),
+            "a{margin:0;color:#fff}"
+        );
+    }
+
+    #[test]
+    fn media_feature_ranges() {
+        assert_eq!(
+            minify!("@media (200px < width <= 400px) { a { b: c } }"),
+            "@media (200px<width<=400px){a{b:c}}"
+        );
+        assert_eq!(
+            minify!("@media (400px >= height > 100px) and (width = 10px) { a { b: c } }"),
+            "@media (400px>=height>100px) and (width=10px){a{b:c}}"
+        );
+        assert_eq!(
+            minify!("@container card ( inline-size >= 30em ) { a { b: c } }"),
+            "@container card (inline-size>=30em){a{b:c}}"
+        );
+        // not a range outside of a bare paren
+        assert_eq!(
+            minify!("@supports selector(a > b) { a > b { c: d } }"),
+            "@supports selector(a > b){a>b{c:d}}"
+        );
+    }
+
+    #[test]
+    fn verbatim() {
+        assert_eq!(
+            minify!(
+                "a { background: -webkit-gradient(linear, left top, left bottom, from(#ffffff), to(rgb(0, 0, 0))); color: #ffffff }"
+            ),
+            "a{background:-webkit-gradient(linear, left top, left bottom, from(#ffffff), to(rgb(0, 0, 0)));color:#fff}"
+        );
+        assert_eq!(
+            minify!(
+                verbatim = "-webkit-box-reflect",
+                verbatim = "filter",
+                "a { -webkit-box-reflect: below 0 linear-gradient(transparent, #ffffff) ; filter : drop-shadow(0 0 2px #000000) ; color: #ffffff }"
+            ),
+            "a{-webkit-box-reflect:below 0 linear-gradient(transparent, #ffffff);filter:drop-shadow(0 0 2px #000000);color:#fff}"
+        );
+        // a preserved comment in a verbatim function may contain quotes and parens
+        assert_eq!(
+            minify!("a{b:-webkit-gradient(/*!'*/);c:-webkit-gradient(/*!)*/ #ffffff)}"),
+            "a{b:-webkit-gradient(/*!'*/);c:-webkit-gradient(/*!)*/#ffffff)}"
+        );
+    }
+
+    #[test]
+    fn identifier_lists() {
+        assert_eq!(
+            minify!("a { transition-property: color , background-color , transform }"),
+            "a{transition-property:color,background-color,transform}"
+        );
+        assert_eq!(
+            minify!(
+                "a { will-change: transform , opacity ; transition: opacity 1s , transform 2s }"
+            ),
+            "a{will-change:transform,opacity;transition:opacity 1s,transform 2s}"
+        );
+        // whitespace between the strings of grid areas must be preserved
+        assert_eq!(
+            minify!(r#"a { grid-template-areas: "header header"  "sidebar main" }"#),
+            r#"a{grid-template-areas:"header header" "sidebar main"}"#
+        );
+    }
+
+    #[test]
+    fn round_decimals() {
+        assert_eq!(
+            minify!(
+                round_decimals,
+                "a { width: 33.33333333333333%; margin: .123456em -1.99999px }"
+            ),
+            "a{width:33.3333%;margin:.1235em -2px}"
+        );
+        assert_eq!(
+            minify!(
+                round_decimals = 2,
+                "a { width: calc(100% / 3 - 0.66666px); opacity: 0.125 }"
+            ),
+            "a{width:calc(100%/3 - .67px);opacity:.13}"
+        );
+        assert_eq!(
+            minify!(round_decimals = 0, "a { line-height: 1.5; width: 12.4px }"),
+            "a{line-height:2;width:12px}"
+        );
+        // integers, short decimals and exponents are untouched
+        assert_eq!(
+            minify!(
+                round_decimals = 2,
+                "a { z-index: 10000; width: 1.5px; height: 1.23456e2px }"
+            ),
+            "a{z-index:10000;width:1.5px;height:1.23456e2px}"
+        );
+        // custom properties, quotes, urls, colors and selectors are untouched
+        assert_eq!(
+            minify!(
+                round_decimals = 2,
+                r#"h1.x33 { --w: 1.23456; content: "1.23456"; background: url(a1.23456.png); color: rgb(0 0 0 / 0.123456) }"#
+            ),
+            r#"h1.x33{--w:1.23456;content:"1.23456";background:url(a1.23456.png);color:#0000001f}"#
+        );
+        assert_eq!(
+            minify!(
+                round_decimals = 2,
+                "@keyframes x { 33.33333% { top: 1.23456px } }"
+            ),
+            "@keyframes x{33.33333%{top:1.23px}}"
+        );
+    }
+
+    #[test]
+    fn page_size() {
+        assert_eq!(
+            minify!("@page landscape { size: A4 landscape; margin: 2cm }"),
+            "@page landscape{size:A4 landscape;margin:2cm}"
+        );
+        assert_eq!(
+            minify!("@page { size: 8.5in  11in ; }"),
+            "@page{size:8.5in 11in}"
+        );
+        assert_eq!(
+            minify!("@page wide:first { size: letter landscape } table { page: wide }"),
+            "@page wide:first{size:letter landscape}table{page:wide}"
+        );
+    }
+
+    #[test]
+    fn shadow_dom_selectors() {
+        assert_eq!(
+            minify!(":host { display: block } :host( .theme ) { color: red }"),
+            ":host{display:block}:host(.theme){color:red}"
+        );
+        assert_eq!(
+            minify!(":host-context( .dark ) ::slotted( span ) { color: white }"),
+            ":host-context(.dark) ::slotted(span){color:#fff}"
+        );
+        assert_eq!(
+            minify!(":host( :hover ) ::slotted( * ) , ::part( label ) { color: red }"),
+            ":host(:hover) ::slotted(*),::part(label){color:red}"
+        );
+    }
+
+    #[test]
+    fn conditional_rules() {
+        assert_eq!(
+            minify!(
+                "@when media( min-width : 600px ) and supports( display : grid ) { a { b: c } } @else supports( display : flex ) { a { b: d } } @else { a { b: e } }"
+            ),
+            "@when media(min-width:600px) and supports(display:grid){a{b:c}}@else supports(display:flex){a{b:d}}@else{a{b:e}}"
+        );
+        assert_eq!(
+            minify!("@when not media( print ) { a { b: c } } @else { a { b: d } }"),
+            "@when not media(print){a{b:c}}@else{a{b:d}}"
+        );
+    }
+
+    #[test]
+    fn color_mix() {
+        assert_eq!(
+            minify!("a { color: color-mix( in srgb , #ff0000 50% , blue ) }"),
+            "a{color:color-mix(in srgb,#f00 50%,blue)}"
+        );
+        assert_eq!(
+            minify!(
+                "a { color: color-mix( in oklch longer hue , rgb( 255 , 0 , 0 ) 30% , hsl( 120 100% 50% ) ) }"
+            ),
+            "a{color:color-mix(in oklch longer hue,#f00 30%,#0f0)}"
+        );
+        assert_eq!(
+            minify!("a { color: color-mix(in srgb, color-mix(in srgb, #ffffff, #000000) 25%, var(--c)) }"),
+            "a{color:color-mix(in srgb,color-mix(in srgb,#fff,#000) 25%,var(--c))}"
+        );
+    }
+
+    #[test]
+    fn rgb_alpha_argument() {
+        // rgb() and rgba() are aliases, so either takes an alpha in either syntax
+        assert_eq!(minify!("a{color:rgb(0, 0, 0, 0.5)}"), "a{color:#00000080}");
+        assert_eq!(minify!("a{color:rgb(0 0 0 / 0.5)}"), "a{color:#00000080}");
+        assert_eq!(minify!("a{color:rgba(0 0 0)}"), "a{color:#000}");
+        assert_eq!(minify!("a{color:rgba(255, 0, 0)}"), "a{color:#f00}");
+        // too many arguments is invalid and left as it is
+        assert_eq!(
+            minify!("a{color:rgb(0, 0, 0, 0.5, 1)}"),
+            "a{color:rgb(0,0,0,.5,1)}"
+        );
+        assert_eq!(
+            minify!("a{color:hsl(0 0% 0% / 0.5 / 1)}"),
+            "a{color:hsl(0 0% 0%/.5/1)}"
+        );
+    }
+
+    #[test]
+    fn hue_units() {
+        assert_eq!(
+            minify!(
+                "a{color:hsl(120deg 100% 50%);b:hsl(0.5turn 100% 50%);c:hsl(200grad,100%,50%)}"
+            ),
+            "a{color:#0f0;b:#0ff;c:#0ff}"
+        );
+        assert_eq!(
+            minify!("a{color:hsla(3.14159rad, 100%, 50%, 0.6)}"),
+            "a{color:#0ff9}"
+        );
+        assert_eq!(minify!("a{color:hsl(120DEG 100% 50%)}"), "a{color:#0f0}");
+        // unknown units, or letters anywhere but the hue, are left as they are
+        assert_eq!(
+            minify!("a{color:hsl(120foo 100% 50%);b:hsl(from red h s l)}"),
+            "a{color:hsl(120foo 100% 50%);b:hsl(from red h s l)}"
+        );
+        assert_eq!(
+            minify!("a{color:hsl(calc(60 * 2) 100% 50%)}"),
+            "a{color:hsl(calc(60*2) 100% 50%)}"
+        );
+        // a unit can only follow the number, and a hue can't be a percentage
+        assert_eq!(
+            minify!("a{color:hsl(deg120 100% 50%);b:hsl(1deg20 100% 50%);c:hsl(120% 100% 50%)}"),
+            "a{color:hsl(deg120 100% 50%);b:hsl(1deg20 100% 50%);c:hsl(120% 100% 50%)}"
+        );
+        assert_eq!(
+            minify!("a{color:hwb(deg120 0% 0%);b:oklch(.5 .1 deg120)}"),
+            "a{color:hwb(deg120 0% 0%);b:oklch(.5 .1 deg120)}"
+        );
+    }
+
+    #[test]
+    fn zero_lengths() {
+        assert_eq!(
+            minify!("a { padding: 0px; margin: 0em 0rem 0vh 0%; top: -0.0px }"),
+            "a{padding:0;margin:0 0 0 0%;top:-0}"
+        );
+        // a zero percentage isn't always equivalent to a zero length
+        assert_eq!(
+            minify!("a { flex-basis: 0%; height: 0%; width: 0% }"),
+            "a{flex-basis:0%;height:0%;width:0%}"
+        );
+        assert_eq!(minify!("a { margin: 0 0 0 0 }"), "a{margin:0 0 0 0}");
+        // only lengths, and not in flex, functions or custom properties
+        assert_eq!(
+            minify!(
+                "a { flex: 0px; -webkit-flex: 1 1 0%; transition: 0s; transform: rotate(0deg) }"
+            ),
+            "a{flex:0px;-webkit-flex:1 1 0%;transition:0s;transform:rotate(0deg)}"
+        );
+        assert_eq!(
+            minify!("a { width: calc(100% - 0px); --gap: 0px; flex-basis: 0px }"),
+            "a{width:calc(100% - 0px);--gap:0px;flex-basis:0}"
+        );
+        // nor in selectors or at-rule preludes
+        assert_eq!(
+            minify!("@media (min-width: 0px) { .x0px { b: c } } @keyframes x { 0% { top: 0px } }"),
+            "@media (min-width:0px){.x0px{b:c}}@keyframes x{0%{top:0}}"
+        );
+        // numbers ending in zero, and units starting with a length unit, are untouched
+        assert_eq!(
+            minify!("a { width: 10px; height: 0.10em; grid-template-columns: 0fr 100px }"),
+            "a{width:10px;height:.10em;grid-template-columns:0fr 100px}"
+        );
+        assert_eq!(
+            minify!(collapse_zeros, "a { margin: 0px 0% }"),
+            "a{margin:0}"
+        );
+    }
+
+    #[test]
+    fn trailing_content() {
+        // stray content after the final rule is collapsed and copied like a selector would be
+        assert_eq!(minify!("a{color:red} garbage"), "a{color:red}garbage");
+        assert_eq!(
+            minify!("a{color:red} garbage  more ;"),
+            "a{color:red}garbage more;"
+        );
+        assert_eq!(minify!("a{color:red} ; ;"), "a{color:red};");
+        assert_eq!(minify!("a{color:red}}"), "a{color:red}}");
+        assert_eq!(minify!("a{color:red}   \n\t  "), "a{color:red}");
+    }
+
+    #[test]
+    fn leading_zeros() {
+        assert_eq!(
+            minify!("a { opacity: 0.75; margin: 0.5em -0.25em 0 1.0px; }"),
+            "a{opacity:.75;margin:.5em -.25em 0 1.0px}"
+        );
+        assert_eq!(
+            minify!("a { content: \"0.5\"; background: url(a0.5.png); --x: 0.5; }"),
+            "a{content:\"0.5\";background:url(a0.5.png);--x:0.5}"
+        );
+    }
+
+    #[test]
+    fn to_file() {
+        // OUT_DIR is only set for a crate with a build script, so the fixture crate is tested
+        let status = std::process::Command::new(env!("CARGO"))
+            .args(["test", "--quiet", "--offline", "--manifest-path"])
+            .arg(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/to_file/Cargo.toml"
+            ))
+            .env(
+                "CARGO_TARGET_DIR",
+                concat!(env!("CARGO_TARGET_TMPDIR"), "/to_file"),
+            )
+            .status()
+            .unwrap();
+        assert!(status.success());
+    }
+
+    #[test]
+    fn named_colors() {
+        assert_eq!(minify!("a { color: white; }"), "a{color:#fff}");
+        assert_eq!(minify!("a { background: black }"), "a{background:#000}");
+        assert_eq!(
+            minify!("a { color: LightGoldenrodYellow !important; }"),
+            "a{color:#fafad2!important}"
+        );
+        // hex colors are never substituted with a name, and names no shorter are left alone
+        assert_eq!(
+            minify!("a { color: #ff0000; background: red; }"),
+            "a{color:#f00;background:red}"
+        );
+        // only a whole value is substituted
+        assert_eq!(
+            minify!("a { border: 1px solid white; animation: black 1s; }"),
+            "a{border:1px solid white;animation:black 1s}"
+        );
+        assert_eq!(
+            minify!("a:white { content: \"white\"; --c: white; color: whitesmoke; }"),
+            "a:white{content:\"white\";--c:white;color:#f5f5f5}"
+        );
+        assert_eq!(
+            minify!("a { border-top-color: white; outline: black; fill: white; stroke: Black }"),
+            "a{border-top-color:#fff;outline:#000;fill:#fff;stroke:#000}"
+        );
+        // only the value of a color property is substituted, as other properties may take an
+        // identifier which happens to be a color name
+        assert_eq!(
+            minify!(
+                "a { animation-name: white; font-family: Black; grid-area: black;
+                counter-reset: white; list-style-type: black }"
+            ),
+            "a{animation-name:white;font-family:Black;grid-area:black;counter-reset:white;\
+            list-style-type:black}"
+        );
+    }
+
+    #[test]
+    fn font_settings() {
+        assert_eq!(
+            minify!(r#"a { font-variation-settings: "wght" 400 , "slnt" -10 ; }"#),
+            r#"a{font-variation-settings:"wght" 400,"slnt" -10}"#
+        );
+        assert_eq!(
+            minify!(r#"a { font-variation-settings : 'wdth' 0.5, "opsz"  32 }"#),
+            r#"a{font-variation-settings:'wdth' .5,"opsz" 32}"#
+        );
+        assert_eq!(
+            minify!(r#"a { font-feature-settings: "liga" 0 , "ss01" , "cv05" 2 ; }"#),
+            r#"a{font-feature-settings:"liga" 0,"ss01","cv05" 2}"#
+        );
+        assert_eq!(
+            minify!(r#"a { font-feature-settings: "smcp" on, "swsh" off; }"#),
+            r#"a{font-feature-settings:"smcp" on,"swsh" off}"#
+        );
+    }
+
+    #[test]
+    fn source_relative_path() {
+        // not found relative to the crate root, so resolved relative to this file
+        assert_eq!(minify!("./relative/style.css"), ".relative{color:#fff}");
+        assert_eq!(minify!("relative/style.css"), ".relative{color:#fff}");
+    }
+
+    #[test]
+    fn svg_data_uri() {
+        // quoted data uris are copied byte for byte, whatever their content
+        assert_eq!(
+            minify!(
+                r#".icon {
+                    background: url("data:image/svg+xml,%3Csvg xmlns='http://www.w3.org/2000/svg' viewBox='0 0 16 16'%3E%3Cpath fill='%23ffffff' d='M0 0h16v16H0z'/%3E%3C/svg%3E") no-repeat ;
+                }"#
+            ),
+            r#".icon{background:url("data:image/svg+xml,%3Csvg xmlns='http://www.w3.org/2000/svg' viewBox='0 0 16 16'%3E%3Cpath fill='%23ffffff' d='M0 0h16v16H0z'/%3E%3C/svg%3E") no-repeat}"#
+        );
+        assert_eq!(
+            minify!(
+                r##"a { background-image: url('data:image/svg+xml;utf8,<svg xmlns="http://www.w3.org/2000/svg">  <rect width="100%" fill="#ffffff"/> </svg>') }"##
+            ),
+            r##"a{background-image:url('data:image/svg+xml;utf8,<svg xmlns="http://www.w3.org/2000/svg">  <rect width="100%" fill="#ffffff"/> </svg>')}"##
+        );
+        // an unquoted data uri can't contain whitespace, and is otherwise copied as it is
+        assert_eq!(
+            minify!("a { mask: url( data:image/svg+xml,%3Csvg%3E%3C/svg%3E ) }"),
+            "a{mask:url(data:image/svg+xml,%3Csvg%3E%3C/svg%3E)}"
+        );
+    }
+
+    #[test]
+    fn multiple_files() {
+        assert_eq!(
+            minify!("./tests/media/screen.css", "./tests/media/print.css"),
+            minify!("./tests/media/screen.css").to_string() + minify!("./tests/media/print.css")
+        );
+        assert_eq!(
+            minify!(
+                collapse_zeros,
+                "./tests/media/screen.css",
+                "./tests/test.css"
+            ),
+            minify!(collapse_zeros, "./tests/media/screen.css").to_string()
+                + minify!(collapse_zeros, "./tests/test.css")
+        );
+        minify!(CSS => "./tests/media/screen.css", "./tests/media/print.css");
+        assert_eq!(
+            CSS,
+            minify!("./tests/media/screen.css", "./tests/media/print.css")
+        );
+    }
+
+    #[test]
+    fn combinators() {
+        assert_eq!(minify!("div > p { color: red }"), "div>p{color:red}");
+        assert_eq!(minify!("a + b { color: red }"), "a+b{color:red}");
+        assert_eq!(minify!("ul ~ li { color: red }"), "ul~li{color:red}");
+        assert_eq!(
+            minify!("a:hover > b, c ~ d + e { f { > g { color: red } } }"),
+            "a:hover>b,c~d+e{f{>g{color:red}}}"
+        );
+        // not combinators
+        assert_eq!(
+            minify!(r#"a { content: "a + b > c ~ d"; }"#),
+            r#"a{content:"a + b > c ~ d"}"#
+        );
+        assert_eq!(
+            minify!("a { width: calc(100% + 2px); margin: 0 +1px; --x: a > b; }"),
+            "a{width:calc(100% + 2px);margin:0 +1px;--x:a > b}"
+        );
+        assert_eq!(
+            minify!("[class~=a] { color: red }"),
+            "[class~=a]{color:red}"
+        );
+    }
+
+    #[test]
+    fn selector_without_block() {
+        //should not panic
+        assert_eq!(minify!(".foo"), ".foo");
+        assert_eq!(minify!("  .foo , .bar  "), ".foo,.bar");
+        assert_eq!(minify!(".foo:hover"), ".foo:hover");
+        assert_eq!(minify!(".foo :hover"), ".foo :hover");
+        assert_eq!(minify!(".foo > .bar"), ".foo>.bar");
+    }
+
+    #[test]
+    fn transparent() {
+        assert_eq!(
+            minify!("a { background: transparent; }"),
+            "a{background:#0000}"
+        );
+        assert_eq!(
+            minify!("a { border: 1px solid TRANSPARENT !important }"),
+            "a{border:1px solid #0000!important}"
+        );
+        assert_eq!(
+            minify!("a { background: linear-gradient(transparent, #ffffff) }"),
+            "a{background:linear-gradient(#0000,#fff)}"
+        );
+        // not a color value
+        assert_eq!(
+            minify!(
+                r#".transparent, a:transparent { content: "transparent"; --t: transparent;
+                background: url(transparent.png); transition: is-transparent 1s }"#
+            ),
+            r#".transparent,a:transparent{content:"transparent";--t:transparent;background:url(transparent.png);transition:is-transparent 1s}"#
+        );
+        assert_eq!(
+            minify!(
+                "a { box-shadow: 0 0 1px transparent; mask-image: linear-gradient(red, transparent);
+                filter: drop-shadow(0 0 2px transparent) }"
+            ),
+            "a{box-shadow:0 0 1px #0000;mask-image:linear-gradient(red,#0000);\
+            filter:drop-shadow(0 0 2px #0000)}"
+        );
+        // an identifier which happens to be `transparent`, in a value which doesn't take a color
+        assert_eq!(
+            minify!(
+                "a { font-family: Transparent, sans-serif; animation: transparent 1s;
+                animation-name: transparent; grid-area: transparent }"
+            ),
+            "a{font-family:Transparent,sans-serif;animation:transparent 1s;\
+            animation-name:transparent;grid-area:transparent}"
+        );
+    }
+
+    #[test]
+    fn strict() {
+        assert_eq!(
+            minify_strict!("./tests/media/screen.css"),
+            minify!("./tests/media/screen.css")
+        );
+        assert_eq!(
+            minify_strict!(quiet, "a { color: #ffffff; }"),
+            "a{color:#fff}"
+        );
+    }
+
+    #[test]
+    fn max_color_error() {
+        // channels which map exactly to a byte are converted
+        assert_eq!(
+            minify!(
+                max_color_error,
+                "a { color: rgb(20%, 40%, 60%); background: hsl(0 100% 50%) }"
+            ),
+            "a{color:#369;background:#f00}"
+        );
+        // 33% is 84.15 out of 255, and hsl(180 50 50) has channels 63.75 and 191.25
+        assert_eq!(
+            minify!(
+                max_color_error,
+                "a { color: rgb(33%, 0%, 0%); background: hsl(180 50 50) }"
+            ),
+            "a{color:rgb(33%,0%,0%);background:hsl(180 50 50)}"
+        );
+        assert_eq!(
+            minify!(
+                max_color_error = 0.2,
+                "a { color: rgb(33%, 0%, 0%); background: hsl(180 50 50) }"
+            ),
+            "a{color:#540000;background:hsl(180 50 50)}"
+        );
+        assert_eq!(
+            minify!(
+                max_color_error = 0.5,
+                "a { color: rgb(33%, 0%, 0%); background: hsl(180 50 50) }"
+            ),
+            "a{color:#540000;background:#40bfbf}"
+        );
+    }
+
+    #[test]
+    fn multiple_slashes() {
+        assert_eq!(
+            minify!("a { mask-border: url(border.png) 25 / 35px / 10px repeat; }"),
+            "a{mask-border:url(border.png) 25/35px/10px repeat}"
+        );
+        assert_eq!(
+            minify!("a { border-image: url( b.png ) 30 / 30px / 30px round }"),
+            "a{border-image:url(b.png) 30/30px/30px round}"
+        );
+        assert_eq!(
+            minify!(
+                "a { border-image: linear-gradient(#ffffff, #000000) 30 30 / 1em 2em / 0 stretch }"
+            ),
+            "a{border-image:linear-gradient(#fff,#000) 30 30/1em 2em/0 stretch}"
+        );
+    }
+
+    #[test]
+    fn raw_str_delimiter() {
+        assert_eq!(
+            minify!(r#####"a::before { content: "\"####"; }"#####),
+            r#####"a::before{content:"\"####"}"#####
+        );
+        assert_eq!(
+            minify!(r######"a::before { content: '"#####' }"######),
+            r######"a::before{content:'"#####'}"######
+        );
+        minify!(CSS => "a::before { content: '\"####' }");
+        assert_eq!(CSS, "a::before{content:'\"####'}");
+    }
+
+    #[test]
+    fn hwb_func() {
+        assert_eq!(minify!("a{color:hwb(120 30% 50%)}"), "a{color:#4d804d}");
+        assert_eq!(minify!("a{color:hwb( 0 0% 0% )}"), "a{color:#f00}");
+        assert_eq!(minify!("a{color:hwb(0 100% 0%)}"), "a{color:#fff}");
+        assert_eq!(minify!("a{color:hwb(0 0% 100%)}"), "a{color:#000}");
+        assert_eq!(minify!("a{color:hwb(0.5turn 0% 0%)}"), "a{color:#0ff}");
+        assert_eq!(minify!("a{color:hwb(240deg 0% 0%)}"), "a{color:#00f}");
+        // whiteness and blackness adding up to more than 100% make a grey
+        assert_eq!(minify!("a{color:hwb(0 60% 60%)}"), "a{color:#808080}");
+        assert_eq!(minify!("a{color:hwb(0 0% 0% / 20%)}"), "a{color:#f003}");
+        assert_eq!(minify!("a{color:hwb(0 0% 0% / 1)}"), "a{color:#f00}");
+        // non-literal expressions are left alone
+        assert_eq!(
+            minify!("a{color:hwb(var(--h) 0% 0%)}"),
+            "a{color:hwb(var(--h) 0% 0%)}"
+        );
+        assert_eq!(
+            minify!("a{color:hwb(calc(10 + 20) 0% 0%)}"),
+            "a{color:hwb(calc(10 + 20) 0% 0%)}"
+        );
+        assert_eq!(
+            minify!("a{color:hwb(0 0% 0% 0% 0%)}"),
+            "a{color:hwb(0 0% 0% 0% 0%)}"
+        );
+    }
+
+    #[test]
+    fn collapse_shorthands() {
+        assert_eq!(
+            minify!(collapse_shorthands, "a { margin: 10px 10px 10px 10px; }"),
+            "a{margin:10px}"
+        );
+        assert_eq!(
+            minify!(collapse_shorthands, "a { padding: 1px 2px 1px 2px; }"),
+            "a{padding:1px 2px}"
+        );
+        assert_eq!(
+            minify!(collapse_shorthands, "a { inset: 1px 2px 3px 2px; }"),
+            "a{inset:1px 2px 3px}"
+        );
+        assert_eq!(
+            minify!(
+                collapse_shorthands,
+                "a { border-width: 1px 2px 1px; margin-block: 3px 3px }"
+            ),
+            "a{border-width:1px 2px;margin-block:3px}"
+        );
+        assert_eq!(
+            minify!(collapse_shorthands, "a { margin: 1em 1em !important; }"),
+            "a{margin:1em!important}"
+        );
+        // left alone
+        assert_eq!(
+            minify!(
+                collapse_shorthands,
+                "a { margin: 1px 2px 3px 4px; padding: var(--a) var(--a);
+                border-radius: 1px 1px / 2px 2px; border: 1px 1px; --x: 1px 1px }"
+            ),
+            "a{margin:1px 2px 3px 4px;padding:var(--a) var(--a);\
+            border-radius:1px 1px/2px 2px;border:1px 1px;--x:1px 1px}"
+        );
+        assert_eq!(minify!("a { margin: 10px 10px; }"), "a{margin:10px 10px}");
+    }
+
+    #[test]
+    fn grid_template_shorthand() {
+        assert_eq!(
+            minify!(
+                r#"a {
+                    grid-template:
+                        "a  b" 1fr
+                        "c  d" 2fr
+                        / auto 1fr ;
+                }"#
+            ),
+            r#"a{grid-template:"a  b" 1fr "c  d" 2fr/auto 1fr}"#
+        );
+        assert_eq!(
+            minify!(
+                r#"a { grid-template: [header-top] "a a a" [header-bottom] [main-top] "b b b" 1fr [main-bottom] / auto 1fr auto; }"#
+            ),
+            r#"a{grid-template:[header-top] "a a a" [header-bottom] [main-top] "b b b" 1fr [main-bottom]/auto 1fr auto}"#
+        );
+    }
+
+    #[test]
+    fn comment_separators() {
+        // a comment separates tokens, so it mustn't join them
+        assert_eq!(minify!("a { margin: 1px/**/2px }"), "a{margin:1px 2px}");
+        assert_eq!(
+            minify!("a { margin: 1px/* gap */2px }"),
+            "a{margin:1px 2px}"
+        );
+        assert_eq!(minify!("a/* */b { c: d }"), "a b{c:d}");
+        // but where no separation is needed, the comment is just removed
+        assert_eq!(
+            minify!("a { color: red/* */!important; }"),
+            "a{color:red!important}"
+        );
+        assert_eq!(minify!("a { margin: 1px /**/ 2px }"), "a{margin:1px 2px}");
+        assert_eq!(minify!("a/**/{/**/b/**/:/**/c/**/;/**/}"), "a{b:c}");
+        assert_eq!(minify!("a { b: c/**/, d }"), "a{b:c,d}");
+    }
+
+    #[test]
+    fn important_comments() {
+        assert_eq!(
+            minify!("/*! (c) 2024 Example: all rights reserved */\n/* build 123 */\na { color: #ffffff; }"),
+            "/*! (c) 2024 Example: all rights reserved */a{color:#fff}"
+        );
+        assert_eq!(
+            minify!("a { color : red ; /*!  keep  this  */ } /*!\n * license\n */ b { c: d }"),
+            "a{color:red;/*!  keep  this  */}/*!\n * license\n */b{c:d}"
+        );
+        // an unterminated important comment is removed like any other
+        assert_eq!(minify!("a{b:c}/*! unterminated"), "a{b:c}");
+    }
+
+    #[test]
+    fn bytes() {
+        const BYTES: &[u8] = minify_bytes!(r#"a::before { content: "\"é\""; color: #ffffff; }"#);
+        const STR: &str = minify!(r#"a::before { content: "\"é\""; color: #ffffff; }"#);
+        assert_eq!(BYTES, STR.as_bytes());
+        const LEN: usize = minify_bytes!("a { color: red; }").len();
+        assert_eq!(LEN, 12);
+    }
+
+    #[test]
+    fn intrinsic_sizing() {
+        assert_eq!(
+            minify!("a { contain-intrinsic-size: auto 300px; }"),
+            "a{contain-intrinsic-size:auto 300px}"
+        );
+        assert_eq!(
+            minify!("a { contain-intrinsic-size: auto 300px auto 200px; }"),
+            "a{contain-intrinsic-size:auto 300px auto 200px}"
+        );
+        assert_eq!(
+            minify!("a { contain-intrinsic-width: auto 0.5em; }"),
+            "a{contain-intrinsic-width:auto .5em}"
+        );
+        assert_eq!(
+            minify!("a { aspect-ratio: auto 16 / 9; }"),
+            "a{aspect-ratio:auto 16/9}"
+        );
+        assert_eq!(
+            minify!("a { aspect-ratio: 16 / 9 auto; }"),
+            "a{aspect-ratio:16/9 auto}"
+        );
+    }
+
+    #[test]
+    fn shortest_colors() {
+        // hex
+        assert_eq!(minify!(shortest_colors, "a{color:#ff0000}"), "a{color:red}");
+        assert_eq!(minify!(shortest_colors, "a{color:#F00}"), "a{color:red}");
+        assert_eq!(
+            minify!(shortest_colors, "a{color:#ff0000ff}"),
+            "a{color:red}"
+        );
+        assert_eq!(minify!(shortest_colors, "a{color:#d2b48c}"), "a{color:tan}");
+        assert_eq!(
+            minify!(shortest_colors, "a{color:#aabbccff}"),
+            "a{color:#abc}"
+        );
+        assert_eq!(minify!(shortest_colors, "a{color:#abcf}"), "a{color:#abc}");
+        assert_eq!(
+            minify!(shortest_colors, "a{color:#ff000080}"),
+            "a{color:#ff000080}"
+        );
+        assert_eq!(
+            minify!(shortest_colors, "a{color:#123456}"),
+            "a{color:#123456}"
+        );
+        // rgb
+        assert_eq!(
+            minify!(shortest_colors, "a{color:rgb(255,0,0)}"),
+            "a{color:red}"
+        );
+        assert_eq!(
+            minify!(shortest_colors, "a{color:rgba(0,0,128,1)}"),
+            "a{color:navy}"
+        );
+        assert_eq!(
+            minify!(shortest_colors, "a{color:rgb(0 128 128)}"),
+            "a{color:teal}"
+        );
+        assert_eq!(
+            minify!(shortest_colors, "a{color:rgb(0,0,0)}"),
+            "a{color:#000}"
+        );
+        // hsl and hwb
+        assert_eq!(
+            minify!(shortest_colors, "a{color:hsl(0,100%,50%)}"),
+            "a{color:red}"
+        );
+        assert_eq!(
+            minify!(shortest_colors, "a{color:hsl(0 0% 50.196%)}"),
+            "a{color:gray}"
+        );
+        assert_eq!(
+            minify!(shortest_colors, "a{color:hwb(0 0% 0%)}"),
+            "a{color:red}"
+        );
+        // named
+        assert_eq!(minify!(shortest_colors, "a{color:red}"), "a{color:red}");
+        assert_eq!(minify!(shortest_colors, "a{color:white}"), "a{color:#fff}");
+        assert_eq!(
+            minify!(shortest_colors, "a{color:fuchsia}"),
+            "a{color:#f0f}"
+        );
+        assert_eq!(
+            minify!(shortest_colors, "a{color:transparent}"),
+            "a{color:#0000}"
+        );
+        // anywhere in a value
+        assert_eq!(
+            minify!(shortest_colors, "a { border: 1px solid #ff0000; }"),
+            "a{border:1px solid red}"
+        );
+        assert_eq!(
+            minify!(
+                shortest_colors,
+                "a { background: linear-gradient(#ff0000, rgb(210, 180, 140)) }"
+            ),
+            "a{background:linear-gradient(red,tan)}"
+        );
+        assert_eq!(
+            minify!(shortest_colors, "a { color: #ff0000 !important; }"),
+            "a{color:red!important}"
+        );
+        // but not in selectors or custom properties
+        assert_eq!(
+            minify!(shortest_colors, "#f00 { --x: #ff0000; }"),
+            "#f00{--x:#f00}"
+        );
+        // and only with the option set
+        assert_eq!(minify!("a{color:rgb(255,0,0)}"), "a{color:#f00}");
+    }
+
+    #[test]
+    fn rgb_clamped_channels() {
+        assert_eq!(minify!("a{color:rgb(300,0,0)}"), "a{color:#f00}");
+        assert_eq!(minify!("a{color:rgb(-5, 128, 999)}"), "a{color:#0080ff}");
+        assert_eq!(
+            minify!("a{color:rgb(-5 128 999 / 50%)}"),
+            "a{color:#0080ff80}"
+        );
+        assert_eq!(minify!("a{color:rgb(150%, -10%, 50%)}"), "a{color:#ff0080}");
+        // an invalid number is still left alone
+        assert_eq!(minify!("a{color:rgb(1-2,0,0)}"), "a{color:rgb(1-2,0,0)}");
+    }
+
+    #[test]
+    fn important_normalized() {
+        assert_eq!(
+            minify!("a { color: red !important; }"),
+            "a{color:red!important}"
+        );
+        assert_eq!(
+            minify!("a { color: red !IMPORTANT; }"),
+            "a{color:red!important}"
+        );
+        assert_eq!(
+            minify!("a { margin: 1px ! Important; padding: 0 }"),
+            "a{margin:1px!important;padding:0}"
+        );
+        // other `!` tokens are left alone
+        assert_eq!(
+            minify!("a { --x: a !importantly; --y: b !default }"),
+            "a{--x:a !importantly;--y:b !default}"
+        );
+    }
+
+    #[test]
+    fn url_quotes() {
+        assert_eq!(
+            minify!(r#"a { background: url("image.png") }"#),
+            "a{background:url(image.png)}"
+        );
+        assert_eq!(
+            minify!("a { background: URL( 'img/a.png?v=1#x' ) no-repeat }"),
+            "a{background:URL(img/a.png?v=1#x) no-repeat}"
+        );
+        // quotes are kept where they are needed
+        assert_eq!(
+            minify!("a { background: url('a b.png') }"),
+            "a{background:url('a b.png')}"
+        );
+        assert_eq!(
+            minify!("a { background: url('a(1).png') }"),
+            "a{background:url('a(1).png')}"
+        );
+        assert_eq!(
+            minify!(r#"a { background: url("it's.png") }"#),
+            r#"a{background:url("it's.png")}"#
+        );
+        assert_eq!(
+            minify!("a { background: url('') }"),
+            "a{background:url('')}"
+        );
+        assert_eq!(
+            minify!("a { background: url('data:image/gif;base64,R0lGOD') }"),
+            "a{background:url('data:image/gif;base64,R0lGOD')}"
+        );
+        // and around a quote string which isn't the whole url
+        assert_eq!(
+            minify!("a { background: url('a.png' x) }"),
+            "a{background:url('a.png' x)}"
+        );
+    }
+
+    #[test]
+    fn strip_empty_rules() {
+        assert_eq!(
+            minify!(strip_empty_rules, "a {} b { color: red }"),
+            "b{color:red}"
+        );
+        assert_eq!(
+            minify!(strip_empty_rules, "a { } b {  ;; } c { color: red; } d {}"),
+            "c{color:red}"
+        );
+        assert_eq!(
+            minify!(
+                strip_empty_rules,
+                r#"
+                a { color: red }
+                /* only a comment */
+                .b, .c:hover { /* only a comment */ }
+                "#
+            ),
+            "a{color:red}"
+        );
+        // nested rules and conditional group rules left empty are removed too
+        assert_eq!(
+            minify!(
+                strip_empty_rules,
+                "@media (min-width: 1px) { a { } .b { & .c {} } } d { color: red }"
+            ),
+            "d{color:red}"
+        );
+        assert_eq!(
+            minify!(
+                strip_empty_rules,
+                "@supports (display: grid) { a { } b { c: d } }"
+            ),
+            "@supports (display:grid){b{c:d}}"
+        );
+        // but not other at-rules, which can have an effect even when empty
+        assert_eq!(
+            minify!(
+                strip_empty_rules,
+                "@layer base {} @keyframes spin {} @font-face {}"
+            ),
+            "@layer base{}@keyframes spin{}@font-face{}"
+        );
+        // or rules with an important comment
+        assert_eq!(
+            minify!(strip_empty_rules, "a { /*! keep */ } /*! keep */ b { }"),
+            "a{/*! keep */}/*! keep */b{}"
+        );
+        // and only with the option set
+        assert_eq!(minify!("a {} b { color: red }"), "a{}b{color:red}");
+    }
+
+    #[test]
+    fn oklab_func() {
+        assert_eq!(minify!("a{color:oklab(0.6 -0.1 0.1)}"), "a{color:#599233}");
+        assert_eq!(minify!("a{color:oklab(50% 0 0)}"), "a{color:#636363}");
+        assert_eq!(minify!("a{color:oklab(1 0 0 / 50%)}"), "a{color:#ffffff80}");
+        assert_eq!(minify!("a{color:oklab(0 0 0)}"), "a{color:#000}");
+        assert_eq!(
+            minify!("a{color:oklch(0.628 0.2577 29.23)}"),
+            "a{color:#f00}"
+        );
+        assert_eq!(
+            minify!("a{color:oklch(62.8% 64.425% 29.23deg)}"),
+            "a{color:#f00}"
+        );
+        // outside the srgb gamut
+        assert_eq!(
+            minify!("a{color:oklch(0.7 0.15 180)}"),
+            "a{color:oklch(.7 .15 180)}"
+        );
+        assert_eq!(
+            minify!("a{color:oklab(0.5 0.4 -0.4)}"),
+            "a{color:oklab(.5 .4 -.4)}"
+        );
+        // not fully literal, or not valid
+        assert_eq!(
+            minify!("a{color:oklch(0.7 var(--c) 180)}"),
+            "a{color:oklch(.7 var(--c) 180)}"
+        );
+        assert_eq!(
+            minify!("a{color:oklab(0.5, 0, 0)}"),
+            "a{color:oklab(.5,0,0)}"
+        );
+    }
+
+    #[test]
+    fn no_color() {
+        assert_eq!(
+            minify!(
+                no_color,
+                "a { color: #ffffff; background: rgb(0, 0, 0); border: 1px solid #000000 }"
+            ),
+            "a{color:#ffffff;background:rgb(0,0,0);border:1px solid #000000}"
+        );
+        assert_eq!(
+            minify!(
+                no_color,
+                "a { color: white; fill: transparent; stroke: hsl(0, 100%, 50%) } /* gone */"
+            ),
+            "a{color:white;fill:transparent;stroke:hsl(0,100%,50%)}"
+        );
+        assert_eq!(
+            minify!(
+                no_color,
+                "a { color: hwb(0 0% 0%); fill: oklab(1 0 0); opacity: 0.50 }"
+            ),
+            "a{color:hwb(0 0% 0%);fill:oklab(1 0 0);opacity:.50}"
+        );
+        assert_eq!(
+            minify!(no_color, shortest_colors, "a { color: #ff0000 }"),
+            "a{color:#ff0000}"
+        );
+    }
+
+    #[test]
+    fn glob() {
+        assert_eq!(
+            minify!(glob "tests/glob/*.css"),
+            "html,body{margin:0}.button{color:#fff}.card{padding:1em}"
+        );
+        assert_eq!(
+            minify!(glob "tests/glob/?_*.css"),
+            "html,body{margin:0}.button{color:#fff}.card{padding:1em}"
+        );
+        assert_eq!(minify!(glob "./tests/glob/*/*.css"), "nested{color:red}");
+        assert_eq!(
+            minify!(collapse_zeros, glob "tests/glob/b*.css", "tests/test.css"),
+            concat!(".button{color:#fff}", minify!("tests/test.css"))
+        );
+    }
+
+    #[test]
+    fn attribute_selectors() {
+        assert_eq!(
+            minify!(r#"input[type="text"] { color: red }"#),
+            "input[type=text]{color:red}"
+        );
+        assert_eq!(
+            minify!(r#"input[ type = "text" ] { color: red }"#),
+            "input[type=text]{color:red}"
+        );
+        assert_eq!(
+            minify!(r#"a[href^="http"], a[lang|='en'], a[class~="-x"] { color: red }"#),
+            "a[href^=http],a[lang|=en],a[class~=-x]{color:red}"
+        );
+        // values which aren't identifiers keep their quotes
+        assert_eq!(
+            minify!(r#"[data-x="1 2"], [data-x="1a"], [href$=".pdf"], [data-x=""] { color: red }"#),
+            r#"[data-x="1 2"],[data-x="1a"],[href$=".pdf"],[data-x=""]{color:red}"#
+        );
+        // a modifier must be separated from an unquoted value
+        assert_eq!(
+            minify!(r#"[type="a" i], [type = b s], [title ~= "a b" i] { color: red }"#),
+            r#"[type=a i],[type=b s],[title~="a b"i]{color:red}"#
+        );
+        // the space before a bracket is a descendant combinator
+        assert_eq!(
+            minify!(r#"a [ *|href ], .b { & [x="y"] { color: red } }"#),
+            "a [*|href],.b{& [x=y]{color:red}}"
+        );
+    }
+
+    #[test]
+    fn escapes() {
+        assert_eq!(
+            minify!("a::before { content: \"\u{1F600}\"; }"),
+            "a::before{content:\"😀\"}"
+        );
+        assert_eq!(
+            minify!("a::before { content: '\u{e9}\u{00_e8}\x41' }"),
+            "a::before{content:'éèA'}"
+        );
+        assert_eq!(
+            minify!(
+                "a { color: #ffffff; \
+                     margin: 0px; }"
+            ),
+            "a{color:#fff;margin:0}"
+        );
+        assert_eq!(
+            minify!("a::before { content: \"\\\\\"; }"),
+            r#"a::before{content:"\\"}"#
+        );
+    }
+
+    #[test]
+    fn calc_whitespace() {
+        assert_eq!(
+            minify!("a { width: calc( 100% - 10px ); }"),
+            "a{width:calc(100% - 10px)}"
+        );
+        assert_eq!(
+            minify!("a { width: calc( 100% * 2 / 3 + 1px ); }"),
+            "a{width:calc(100%*2/3 + 1px)}"
+        );
+        // nested calc and bare parens
+        assert_eq!(
+            minify!("a { width: calc( 100% - calc( ( 2px + 1em ) * 2 ) ); }"),
+            "a{width:calc(100% - calc((2px + 1em)*2))}"
+        );
+        assert_eq!(
+            minify!("a { width: clamp( 1px , 2 * 1vw , -webkit-calc( 3px * 2 ) ); }"),
+            "a{width:clamp(1px,2*1vw,-webkit-calc(3px*2))}"
+        );
+        // the spaces around `+` and `-` are required
+        assert_eq!(
+            minify!("a { margin: calc(1px + -2px) calc(var(--a) - 1px); }"),
+            "a{margin:calc(1px + -2px) calc(var(--a) - 1px)}"
+        );
+        // and a `*` outside a math function may be a universal selector
+        assert_eq!(
+            minify!("a:is(b *) { width: calc(2 * 1px) }"),
+            "a:is(b *){width:calc(2*1px)}"
+        );
+    }
+
+    #[test]
+    fn dedupe_declarations() {
+        assert_eq!(
+            minify!(dedupe_declarations, "a { color: red; color: red; }"),
+            "a{color:red}"
+        );
+        assert_eq!(
+            minify!(
+                dedupe_declarations,
+                "a { color: #fff; color : #ffffff; color: white; margin: 0 }"
+            ),
+            "a{color:#fff;margin:0}"
+        );
+        // the same property with a different value is a deliberate fallback
+        assert_eq!(
+            minify!(
+                dedupe_declarations,
+                "a { width: 100px; width: calc(100% - 1em); }"
+            ),
+            "a{width:100px;width:calc(100% - 1em)}"
+        );
+        // duplicates which aren't adjacent are kept, as removing one could change the cascade
+        assert_eq!(
+            minify!(
+                dedupe_declarations,
+                "a { color: red; color: blue; color: red }"
+            ),
+            "a{color:red;color:blue;color:red}"
+        );
+        // as are identical declarations in different blocks
+        assert_eq!(
+            minify!(
+                dedupe_declarations,
+                "a { color: red; b { color: red; } color: red } c { color: red }"
+            ),
+            "a{color:red;b{color:red}color:red}c{color:red}"
+        );
+        // and only with the option set
+        assert_eq!(
+            minify!("a { color: red; color: red; }"),
+            "a{color:red;color:red}"
+        );
+    }
+
+    minify!(pub(crate) ITEM_CSS => "./tests/tabs.css");
+    minify!(ITEM_COLLAPSED => collapse_zeros, "a { margin: 0 0; }");
+
+    #[test]
+    fn const_item() {
+        assert_eq!(ITEM_CSS, minify!("./tests/tabs.css"));
+        assert_eq!(ITEM_COLLAPSED, "a{margin:0}");
+        minify!(LOCAL => "a { color: #ffffff; }");
+        assert_eq!(LOCAL, "a{color:#fff}");
+    }
+
+    #[test]
+    fn compound_media_queries() {
+        // feature colons lose their trailing space, but keywords keep theirs
+        assert_eq!(
+            minify!("@media (min-width: 600px) and (max-width: 900px) { a { b: c } }"),
+            "@media (min-width:600px) and (max-width:900px){a{b:c}}"
+        );
+        assert_eq!(
+            minify!(
+                "@media only screen and ( min-width : 600px ) , print and (orientation: landscape)
+                { a { b: c } }"
+            ),
+            "@media only screen and (min-width:600px),print and (orientation:landscape){a{b:c}}"
+        );
+        assert_eq!(
+            minify!("@media (hover: hover) or (min-resolution: 2dppx) { a { b: c } }"),
+            "@media (hover:hover) or (min-resolution:2dppx){a{b:c}}"
+        );
+        assert_eq!(
+            minify!("@supports (display: flex) and (not (display: inline-grid)) { a { b: c } }"),
+            "@supports (display:flex) and (not (display:inline-grid)){a{b:c}}"
+        );
+        assert_eq!(
+            minify!(
+                "@container sidebar (min-width: 400px) and style(--responsive: true) { a { b: c } }"
+            ),
+            "@container sidebar (min-width:400px) and style(--responsive:true){a{b:c}}"
+        );
+    }
+
+    #[test]
+    fn font_weight_keywords() {
+        assert_eq!(minify!("a { font-weight: bold }"), "a{font-weight:700}");
+        assert_eq!(minify!("a { font-weight: normal }"), "a{font-weight:400}");
+        assert_eq!(minify!("a { FONT-WEIGHT: Bold }"), "a{FONT-WEIGHT:700}");
+        assert_eq!(
+            minify!("a { font-weight: bold !important; color: red }"),
+            "a{font-weight:700!important;color:red}"
+        );
+        // other keywords have no equal number
+        assert_eq!(
+            minify!("a { font-weight: bolder }"),
+            "a{font-weight:bolder}"
+        );
+        // `normal` means something else entirely for other properties
+        assert_eq!(
+            minify!("a { white-space: normal }"),
+            "a{white-space:normal}"
+        );
+        assert_eq!(
+            minify!("a { font: bold 12px serif }"),
+            "a{font:bold 12px serif}"
+        );
+        assert_eq!(
+            minify!("a { --font-weight: bold }"),
+            "a{--font-weight:bold}"
+        );
+    }
+
+    #[test]
+    fn empty_declarations() {
+        // semicolons with nothing between them are collapsed wherever they occur in a block
+        assert_eq!(minify!("a{;color:red;;}"), "a{color:red}");
+        assert_eq!(minify!("a { ; ; color: red }"), "a{color:red}");
+        assert_eq!(minify!("a{color:red;;;margin:0}"), "a{color:red;margin:0}");
+        assert_eq!(minify!("a { color: red ; ; }"), "a{color:red}");
+        assert_eq!(minify!("a{;}"), "a{}");
+        assert_eq!(minify!("a{b{;;c:d;}}"), "a{b{c:d}}");
+        assert_eq!(
+            minify!(dedupe_declarations, "a{color:red;;color:red}"),
+            "a{color:red}"
+        );
+    }
 }
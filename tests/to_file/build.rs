@@ -0,0 +1,3 @@
+// no build steps, but having a build script means cargo sets OUT_DIR, which minify_to_file!()
+// writes to
+fn main() {}
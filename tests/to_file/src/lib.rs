@@ -0,0 +1,10 @@
+use const_css_minify::{minify, minify_to_file};
+
+pub const CSS: &str = minify_to_file!("../media/screen.css", "screen.min.css");
+
+#[test]
+fn written() {
+    assert_eq!(CSS, minify!("../media/screen.css"));
+    let written = std::fs::read_to_string(concat!(env!("OUT_DIR"), "/screen.min.css")).unwrap();
+    assert_eq!(written, CSS);
+}
@@ -0,0 +1,8 @@
+use const_css_minify::minify_assert_eq;
+
+minify_assert_eq!(
+    "div { margin: 1px 2px; color: #ffffff; }",
+    "div{margin:1px 2px;color:#fffffe}"
+);
+
+fn main() {}
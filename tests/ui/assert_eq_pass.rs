@@ -0,0 +1,10 @@
+use const_css_minify::minify_assert_eq;
+
+minify_assert_eq!(
+    "div { margin: 1px 2px; color: #ffffff; }",
+    "div{margin:1px 2px;color:#fff}"
+);
+
+fn main() {
+    minify_assert_eq!("a { color: rgb(0, 0, 0) }", "a{color:#000}");
+}
@@ -0,0 +1,8 @@
+use const_css_minify::minify_fragment;
+
+// the escaped brace is part of the selector, so the rule is never opened
+const CSS: &str = minify_fragment!(r".a\{ color: red }");
+
+fn main() {
+    println!("{}", CSS);
+}
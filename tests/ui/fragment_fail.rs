@@ -0,0 +1,7 @@
+use const_css_minify::minify_fragment;
+
+const CSS: &str = minify_fragment!(".a { color: red } .b");
+
+fn main() {
+    println!("{}", CSS);
+}
@@ -0,0 +1,7 @@
+use const_css_minify::minify;
+
+const CSS: &str = minify!(glob "no_such_dir/*.css");
+
+fn main() {
+    println!("{}", CSS);
+}
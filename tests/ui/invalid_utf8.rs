@@ -0,0 +1,7 @@
+use const_css_minify::minify;
+
+const CSS: &str = minify!("../invalid_utf8.css");
+
+fn main() {
+    println!("{}", CSS);
+}
@@ -0,0 +1,7 @@
+use const_css_minify::minify_media;
+
+const CSS: &str = minify_media! { "print" => "../media/prnit.css" };
+
+fn main() {
+    println!("{}", CSS);
+}
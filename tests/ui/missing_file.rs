@@ -0,0 +1,7 @@
+use const_css_minify::minify;
+
+const CSS: &str = minify!("../media/screen.css", "../media/missing.css");
+
+fn main() {
+    println!("{}", CSS);
+}
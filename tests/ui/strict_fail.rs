@@ -0,0 +1,7 @@
+use const_css_minify::minify_strict;
+
+const CSS: &str = minify_strict!("a { : red; b: url(c");
+
+fn main() {
+    println!("{}", CSS);
+}
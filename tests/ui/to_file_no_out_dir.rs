@@ -0,0 +1,8 @@
+use const_css_minify::minify_to_file;
+
+// there is no build script, so OUT_DIR isn't set
+const CSS: &str = minify_to_file!("a { color: red; }", "style.min.css");
+
+fn main() {
+    println!("{}", CSS);
+}
@@ -0,0 +1,8 @@
+use const_css_minify::minify_to_file;
+
+const CSS: &str = minify_to_file!("a { color: red; }", "../style.min.css");
+const ABSOLUTE: &str = minify_to_file!("a { color: red; }", "/tmp/style.min.css");
+
+fn main() {
+    println!("{}{}", CSS, ABSOLUTE);
+}